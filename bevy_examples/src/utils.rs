@@ -6,7 +6,7 @@ use bevy::{
 };
 
 use bevy_procedural_tilemaps::{
-    assets::{BundleInserter, ModelAsset, ModelsAssets},
+    assets::{BundleInserter, ModelAsset, ModelsAssets, NodeSpawnContext, SpawnHook},
     proc_gen::prelude::GridDelta,
 };
 
@@ -19,7 +19,7 @@ pub struct ModelAssetDef {
     pub grid_offset: GridDelta,
     /// Offset in world coordinates
     pub offset: Vec3,
-    pub components_spawner: fn(&mut EntityCommands),
+    pub components_spawner: SpawnHook,
 }
 
 impl ModelAssetDef {
@@ -28,7 +28,7 @@ impl ModelAssetDef {
             path,
             grid_offset: GridDelta::new(0, 0, 0),
             offset: Vec3::ZERO,
-            components_spawner: |_| {},
+            components_spawner: SpawnHook::none(),
         }
     }
 
@@ -43,7 +43,15 @@ impl ModelAssetDef {
     }
 
     pub fn with_components(mut self, spawn_cmds: fn(&mut EntityCommands)) -> Self {
-        self.components_spawner = spawn_cmds;
+        self.components_spawner = SpawnHook::from_commands(spawn_cmds);
+        self
+    }
+
+    pub fn with_spawn_hook(
+        mut self,
+        hook: impl Fn(&mut EntityCommands, &NodeSpawnContext) + Send + Sync + 'static,
+    ) -> Self {
+        self.components_spawner = SpawnHook::new(hook);
         self
     }
 
@@ -76,7 +84,7 @@ where
                     )),
                     grid_offset: asset_def.grid_offset.clone(),
                     world_offset: asset_def.offset,
-                    spawn_commands: asset_def.components_spawner.clone(),
+                    spawn_hook: asset_def.components_spawner.clone(),
                 },
             )
         }
@@ -1,17 +1,27 @@
 use std::{
     collections::HashMap,
+    fmt,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
-use bevy::{ecs::system::EntityCommands, math::Vec3};
+use bevy::{
+    ecs::{entity::Entity, system::EntityCommands},
+    math::Vec3,
+};
 use procedural_tilemaps_core::{
-    generator::model::{ModelIndex, ModelRotation},
-    grid::cartesian::coordinates::GridDelta,
+    generator::{
+        model::{ModelFlip, ModelIndex, ModelInstance, ModelRotation},
+        GeneratedNode,
+    },
+    grid::cartesian::coordinates::{GridDelta, WorldPosition},
 };
 
 /// Defines a struct which can spawn components on an Entity (for example, a [`bevy::sprite::Sprite`], a [`bevy::scene::SceneRoot`], ...).
 pub trait BundleInserter: Sync + Send + Default + 'static {
     /// From the `BundleSpawner` own's struct data and a position, scale and rotation, can modify the spawned node `Entity`
+    ///
+    /// When the generated model is reflected (see [`procedural_tilemaps_core::generator::model::ModelFlip`]), the world axes it mirrors are negated in `scale`, depending on the rotation axis of the [`crate::spawner::NodesSpawner`]. The scale should be applied before the rotation to mirror the spawned assets correctly.
     fn insert_bundle(
         &self,
         command: &mut EntityCommands,
@@ -29,19 +39,186 @@ pub trait BundleInserter: Sync + Send + Default + 'static {
 pub struct ModelAsset<A: BundleInserter> {
     /// Stores handle(s) to the asset(s) and spawns their bundle
     pub assets_bundle: A,
-    /// Spawn commands to add additional components to a spawned model
-    pub spawn_commands: fn(&mut EntityCommands),
+    /// Hook to add additional components to a spawned model
+    pub spawn_hook: SpawnHook,
     /// Grid offset from the generated grid node position. Added to `offset`.
     pub grid_offset: GridDelta,
     /// World offset from the generated grid node position. Added to `grid_offset`.
     pub world_offset: Vec3,
 }
 
+/// Context of a spawned [`ModelAsset`], given to its [`NodeSpawnHook`]
+#[derive(Clone, Copy, Debug)]
+pub struct NodeSpawnContext {
+    /// Entity of the generator, parent of the spawned entity
+    pub generator: Entity,
+    /// Generated node for which the asset is spawned
+    pub node: GeneratedNode,
+    /// Position of the node in world grid units, given by the [`crate::layout::NodeLayout`] of the generator (see [`crate::layout::NodeLayout::world_position`])
+    pub world_position: WorldPosition,
+    /// Translation of the spawned entity, relative to the generator entity
+    pub translation: Vec3,
+}
+
+/// Adds components to the entity spawned for a [`ModelAsset`], depending on the generated node.
+///
+/// Implemented for closures `Fn(&mut EntityCommands, &NodeSpawnContext)`, which can capture data.
+pub trait NodeSpawnHook: Send + Sync + 'static {
+    /// Called once the bundle of the [`ModelAsset`] has been inserted on the spawned entity
+    fn on_spawn(&self, commands: &mut EntityCommands, context: &NodeSpawnContext);
+}
+
+impl<F: Fn(&mut EntityCommands, &NodeSpawnContext) + Send + Sync + 'static> NodeSpawnHook for F {
+    fn on_spawn(&self, commands: &mut EntityCommands, context: &NodeSpawnContext) {
+        self(commands, context)
+    }
+}
+
+/// Optional shared [`NodeSpawnHook`] of a [`ModelAsset`]. Cloning a [`SpawnHook`] shares the same hook.
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::{assets::NodeSpawnContext, prelude::*};
+///
+/// #[derive(Component)]
+/// struct Collider(Vec2);
+///
+/// #[derive(Component)]
+/// struct Tile(WorldPosition);
+///
+/// let mut sockets = SocketCollection::new();
+/// let a = sockets.create();
+/// sockets.add_connection(a, vec![a]);
+/// let mut models = ModelCollection::<Cartesian3D>::new();
+/// models.create(SocketsCartesian3D::Mono(a));
+/// models.create(SocketsCartesian3D::Mono(a));
+/// let rules = RulesBuilder::new_cartesian_3d(models, sockets).build().unwrap();
+/// let generator = GeneratorBuilder::new()
+///     .with_rules(rules)
+///     .with_grid(
+///         CartesianGrid::new_cartesian_3d(4, 4, 1, false, false, false)
+///             .with_origin(WorldPosition::new(8, 0, 0)),
+///     )
+///     .build()
+///     .unwrap();
+///
+/// // Hooks can capture data, and receive the generated node and its position
+/// let size = Vec2::new(16., 8.);
+/// let hook = SpawnHook::new(move |commands: &mut EntityCommands, context: &NodeSpawnContext| {
+///     commands.insert((Collider(size), Tile(context.world_position)));
+/// });
+/// let mut models_assets = ModelsAssets::<Handle<Image>>::new();
+/// for model_index in 0..2 {
+///     models_assets.add(model_index, ModelAsset {
+///         assets_bundle: Handle::default(),
+///         spawn_hook: hook.clone(),
+///         grid_offset: GridDelta::new(0, 0, 0),
+///         world_offset: Vec3::ZERO,
+///     });
+/// }
+/// let spawner = NodesSpawner::new(models_assets, Vec3::ONE, Vec3::ONE);
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     ProcGenSimplePlugin::<Cartesian3D, Handle<Image>>::default(),
+/// ));
+/// app.world_mut().spawn((generator, spawner));
+/// app.update();
+///
+/// let mut tiles = app.world_mut().query::<(&Tile, &Collider)>();
+/// assert_eq!(tiles.iter(app.world()).count(), 16);
+/// assert!(tiles.iter(app.world()).all(|(_, collider)| collider.0 == size));
+/// assert!(tiles.iter(app.world()).all(|(tile, _)| (8..12).contains(&tile.0.x)));
+/// ```
+#[derive(Clone, Default)]
+pub struct SpawnHook(Option<Arc<dyn NodeSpawnHook>>);
+
+impl SpawnHook {
+    /// A [`SpawnHook`] which does nothing
+    pub fn none() -> Self {
+        Self(None)
+    }
+
+    /// Creates a [`SpawnHook`] from a closure, which receives the [`NodeSpawnContext`] of the spawned entity
+    pub fn new<F: Fn(&mut EntityCommands, &NodeSpawnContext) + Send + Sync + 'static>(
+        hook: F,
+    ) -> Self {
+        Self(Some(Arc::new(hook)))
+    }
+
+    /// Creates a [`SpawnHook`] from any [`NodeSpawnHook`]
+    pub fn from_hook(hook: impl NodeSpawnHook) -> Self {
+        Self(Some(Arc::new(hook)))
+    }
+
+    /// Creates a [`SpawnHook`] from a function which does not need the [`NodeSpawnContext`]
+    pub fn from_commands(spawn_commands: fn(&mut EntityCommands)) -> Self {
+        Self::new(move |commands: &mut EntityCommands, _: &NodeSpawnContext| {
+            spawn_commands(commands)
+        })
+    }
+
+    /// Returns `true` if there is a hook to run
+    pub fn is_some(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Runs the hook, if any
+    pub fn run(&self, commands: &mut EntityCommands, context: &NodeSpawnContext) {
+        if let Some(hook) = &self.0 {
+            hook.on_spawn(commands, context);
+        }
+    }
+}
+
+impl fmt::Debug for SpawnHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(_) => write!(f, "SpawnHook(Some(..))"),
+            None => write!(f, "SpawnHook(None)"),
+        }
+    }
+}
+
 /// Defines a map which links a `Model` via its [`ModelIndex`] to his spawnable(s) [`ModelAsset`]
+///
+/// A rotated (or reflected) model can also have its own pre-drawn assets (for example sprites with baked lighting or shadows), see [`ModelsAssets::add_rotated`] and [`ModelsAssets::add_variant`]. They are spawned un-rotated and un-reflected, while the assets of the model are transformed only as a fallback.
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::{prelude::*, proc_gen::generator::model::ModelInstance};
+///
+/// let model_asset = |world_offset| ModelAsset {
+///     assets_bundle: Handle::<Image>::default(),
+///     spawn_hook: SpawnHook::none(),
+///     grid_offset: GridDelta::new(0, 0, 0),
+///     world_offset,
+/// };
+/// let mut models_assets = ModelsAssets::new();
+/// models_assets.add(0, model_asset(Vec3::ZERO));
+/// models_assets.add_rotated(0, ModelRotation::Rot90, model_asset(Vec3::X));
+///
+/// models_assets.add_variant(0, ModelRotation::Rot0, ModelFlip::X, model_asset(Vec3::Y));
+///
+/// let instance = |rotation, flip| ModelInstance { model_index: 0, rotation, flip, hex_rotation: HexRotation::Rot0 };
+/// // The pre-drawn variants are neither rotated nor reflected
+/// let (assets, rotation, flip) = models_assets.assets_for(&instance(ModelRotation::Rot90, ModelFlip::None)).unwrap();
+/// assert_eq!((assets[0].world_offset, rotation, flip), (Vec3::X, ModelRotation::Rot0, ModelFlip::None));
+/// let (assets, rotation, flip) = models_assets.assets_for(&instance(ModelRotation::Rot0, ModelFlip::X)).unwrap();
+/// assert_eq!((assets[0].world_offset, rotation, flip), (Vec3::Y, ModelRotation::Rot0, ModelFlip::None));
+/// // Other variants fall back to transforming the assets of the model
+/// let (assets, rotation, flip) = models_assets.assets_for(&instance(ModelRotation::Rot90, ModelFlip::X)).unwrap();
+/// assert_eq!((assets[0].world_offset, rotation, flip), (Vec3::ZERO, ModelRotation::Rot90, ModelFlip::X));
+/// ```
 #[derive(Debug)]
 pub struct ModelsAssets<A: BundleInserter> {
     /// Only contains a ModelIndex if there are some assets for it. One model may have multiple [`ModelAsset`].
     map: HashMap<ModelIndex, Vec<ModelAsset<A>>>,
+    /// Per-variant overrides of the assets in `map`
+    variants: HashMap<(ModelIndex, ModelRotation, ModelFlip), Vec<ModelAsset<A>>>,
 }
 impl<A: BundleInserter> Deref for ModelsAssets<A> {
     type Target = HashMap<ModelIndex, Vec<ModelAsset<A>>>;
@@ -56,11 +233,22 @@ impl<A: BundleInserter> DerefMut for ModelsAssets<A> {
     }
 }
 
+/// Key of a model variant, in the canonical form emitted by the generator
+pub(crate) fn variant_key(
+    index: ModelIndex,
+    rotation: ModelRotation,
+    flip: ModelFlip,
+) -> (ModelIndex, ModelRotation, ModelFlip) {
+    let (flip, rotation) = flip.canonical(rotation);
+    (index, rotation, flip)
+}
+
 impl<A: BundleInserter> ModelsAssets<A> {
     /// Create a new ModelsAssets with an empty map
     pub fn new() -> Self {
         Self {
             map: Default::default(),
+            variants: Default::default(),
         }
     }
 
@@ -75,4 +263,97 @@ impl<A: BundleInserter> ModelsAssets<A> {
             }
         }
     }
+
+    /// Adds a [`ModelAsset`] to the model `index` when it is generated with the rotation `rotation` (and no reflection).
+    ///
+    /// Once a rotation has its own assets, they replace the assets of the model for this rotation, and are spawned without being rotated.
+    pub fn add_rotated(
+        &mut self,
+        index: ModelIndex,
+        rotation: ModelRotation,
+        model_asset: ModelAsset<A>,
+    ) {
+        self.add_variant(index, rotation, ModelFlip::None, model_asset);
+    }
+
+    /// Adds a [`ModelAsset`] to the model `index` when it is generated with the rotation `rotation` and the reflection `flip`.
+    ///
+    /// Once a variant has its own assets, they replace the assets of the model for this variant, and are spawned without being rotated nor reflected.
+    ///
+    /// Variants are keyed by their canonical form (see [`ModelFlip::canonical`]), so a variant added with [`ModelFlip::Y`] or [`ModelFlip::XY`] is used for the equivalent instances emitted by the generator.
+    ///
+    /// ### Example
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_procedural_tilemaps::{prelude::*, proc_gen::generator::model::ModelInstance};
+    ///
+    /// let mut models_assets = ModelsAssets::new();
+    /// models_assets.add_variant(
+    ///     0,
+    ///     ModelRotation::Rot0,
+    ///     ModelFlip::Y,
+    ///     ModelAsset {
+    ///         assets_bundle: Handle::<Image>::default(),
+    ///         spawn_hook: SpawnHook::none(),
+    ///         grid_offset: GridDelta::new(0, 0, 0),
+    ///         world_offset: Vec3::Y,
+    ///     },
+    /// );
+    ///
+    /// // A Y reflection is generated as an X reflection rotated by 180°
+    /// let instance = ModelInstance {
+    ///     model_index: 0,
+    ///     rotation: ModelRotation::Rot180,
+    ///     flip: ModelFlip::X,
+    ///     hex_rotation: HexRotation::Rot0,
+    /// };
+    /// let (assets, rotation, flip) = models_assets.assets_for(&instance).unwrap();
+    /// assert_eq!((assets[0].world_offset, rotation, flip), (Vec3::Y, ModelRotation::Rot0, ModelFlip::None));
+    /// ```
+    pub fn add_variant(
+        &mut self,
+        index: ModelIndex,
+        rotation: ModelRotation,
+        flip: ModelFlip,
+        model_asset: ModelAsset<A>,
+    ) {
+        self.variants
+            .entry(variant_key(index, rotation, flip))
+            .or_default()
+            .push(model_asset);
+    }
+
+    /// Returns the assets of the model `index` for the rotation `rotation` (and no reflection), if it has its own assets
+    pub fn get_rotated(
+        &self,
+        index: ModelIndex,
+        rotation: ModelRotation,
+    ) -> Option<&Vec<ModelAsset<A>>> {
+        self.get_variant(index, rotation, ModelFlip::None)
+    }
+
+    /// Returns the assets of the model `index` for the rotation `rotation` and the reflection `flip`, if it has its own assets
+    pub fn get_variant(
+        &self,
+        index: ModelIndex,
+        rotation: ModelRotation,
+        flip: ModelFlip,
+    ) -> Option<&Vec<ModelAsset<A>>> {
+        self.variants.get(&variant_key(index, rotation, flip))
+    }
+
+    /// Returns the assets to spawn for a model instance, with the rotation and the reflection to apply to them.
+    ///
+    /// Returns the assets of its variant with [`ModelRotation::Rot0`] and [`ModelFlip::None`] if there are some (see [`ModelsAssets::add_variant`]), or else the assets of its model with the rotation and the reflection of the instance.
+    pub fn assets_for(
+        &self,
+        instance: &ModelInstance,
+    ) -> Option<(&Vec<ModelAsset<A>>, ModelRotation, ModelFlip)> {
+        match self.get_variant(instance.model_index, instance.rotation, instance.flip) {
+            Some(assets) => Some((assets, ModelRotation::Rot0, ModelFlip::None)),
+            None => self
+                .get(&instance.model_index)
+                .map(|assets| (assets, instance.rotation, instance.flip)),
+        }
+    }
 }
@@ -0,0 +1,256 @@
+use std::{collections::HashMap, fmt};
+
+use bevy::{
+    asset::{Assets, Handle},
+    image::{
+        Image, TextureAtlas, TextureAtlasBuilder, TextureAtlasBuilderError, TextureAtlasLayout,
+    },
+    math::Vec3,
+    sprite::Sprite,
+};
+use procedural_tilemaps_core::{
+    generator::model::{ModelIndex, ModelRotation},
+    grid::cartesian::coordinates::GridDelta,
+};
+
+use crate::assets::{ModelAsset, ModelsAssets, SpawnHook};
+
+/// Reference to a tile of a texture atlas, by index in its [`TextureAtlasLayout`] or by name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AtlasTile {
+    /// Index of the tile in the [`TextureAtlasLayout`]
+    Index(usize),
+    /// Name of the tile, see [`AtlasAssetsBuilder::with_tile_names`]
+    Name(String),
+}
+
+impl From<usize> for AtlasTile {
+    fn from(index: usize) -> Self {
+        AtlasTile::Index(index)
+    }
+}
+
+impl From<&str> for AtlasTile {
+    fn from(name: &str) -> Self {
+        AtlasTile::Name(name.to_string())
+    }
+}
+
+impl From<String> for AtlasTile {
+    fn from(name: String) -> Self {
+        AtlasTile::Name(name)
+    }
+}
+
+/// Error returned by [`AtlasAssetsBuilder::build`] when a tile name was not registered
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownAtlasTile(pub String);
+
+impl fmt::Display for UnknownAtlasTile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Unknown atlas tile name: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownAtlasTile {}
+
+/// Builds [`ModelsAssets`] of [`Sprite`]s sharing a single atlas image and [`TextureAtlasLayout`], from per-model tile indices or names.
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::{atlas::AtlasAssetsBuilder, prelude::*};
+///
+/// let image = Handle::<Image>::default();
+/// let layout = Handle::<TextureAtlasLayout>::default();
+/// let models_assets = AtlasAssetsBuilder::new(image, layout)
+///     .with_tile_names(["grass", "water", "sand"])
+///     .add_tile(0, "grass")
+///     .add_tile(1, 2)
+///     .add_tile_with_offsets(1, "water", GridDelta::new(0, 0, 1), Vec3::ZERO)
+///     .add_rotated_tile(0, ModelRotation::Rot90, "sand")
+///     .build()
+///     .unwrap();
+///
+/// let tiles: Vec<usize> = models_assets[&1]
+///     .iter()
+///     .map(|asset| asset.assets_bundle.texture_atlas.as_ref().unwrap().index)
+///     .collect();
+/// assert_eq!(tiles, vec![2, 1]);
+/// let rotated = &models_assets.get_rotated(0, ModelRotation::Rot90).unwrap()[0];
+/// assert_eq!(rotated.assets_bundle.texture_atlas.as_ref().unwrap().index, 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AtlasAssetsBuilder {
+    image: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+    names: HashMap<String, usize>,
+    tiles: Vec<(
+        ModelIndex,
+        Option<ModelRotation>,
+        AtlasTile,
+        GridDelta,
+        Vec3,
+    )>,
+}
+
+impl AtlasAssetsBuilder {
+    /// Creates a new [`AtlasAssetsBuilder`] for the atlas `image`, with tiles defined by `layout`
+    pub fn new(image: Handle<Image>, layout: Handle<TextureAtlasLayout>) -> Self {
+        Self {
+            image,
+            layout,
+            names: HashMap::new(),
+            tiles: Vec::new(),
+        }
+    }
+
+    /// Packs loaded images into a new atlas image and [`TextureAtlasLayout`], and names each tile after its given name.
+    ///
+    /// All the images must be loaded in `images`. Tiles are indexed in the order of `tiles`.
+    ///
+    /// ### Example
+    /// ```
+    /// use bevy::{asset::RenderAssetUsages, prelude::*, render::render_resource::{Extent3d, TextureDimension, TextureFormat}};
+    /// use bevy_procedural_tilemaps::atlas::AtlasAssetsBuilder;
+    ///
+    /// let mut images = Assets::<Image>::default();
+    /// let mut layouts = Assets::<TextureAtlasLayout>::default();
+    /// let mut tile = |color: [u8; 4]| {
+    ///     images.add(Image::new_fill(
+    ///         Extent3d { width: 16, height: 16, depth_or_array_layers: 1 },
+    ///         TextureDimension::D2,
+    ///         &color,
+    ///         TextureFormat::Rgba8UnormSrgb,
+    ///         RenderAssetUsages::default(),
+    ///     ))
+    /// };
+    /// let tiles = [("grass", tile([0, 255, 0, 255])), ("water", tile([0, 0, 255, 255]))];
+    ///
+    /// let builder = AtlasAssetsBuilder::pack(tiles, &mut images, &mut layouts).unwrap();
+    /// assert_eq!(builder.tile_index("water"), Some(1));
+    /// assert_eq!(layouts.get(builder.layout()).unwrap().len(), 2);
+    /// ```
+    pub fn pack<S: Into<String>>(
+        tiles: impl IntoIterator<Item = (S, Handle<Image>)>,
+        images: &mut Assets<Image>,
+        layouts: &mut Assets<TextureAtlasLayout>,
+    ) -> Result<Self, TextureAtlasBuilderError> {
+        let mut names = HashMap::new();
+        let (layout, atlas_image) = {
+            let mut atlas_builder = TextureAtlasBuilder::default();
+            for (index, (name, handle)) in tiles.into_iter().enumerate() {
+                let Some(image) = images.get(&handle) else {
+                    return Err(TextureAtlasBuilderError::UninitializedSourceTexture);
+                };
+                atlas_builder.add_texture(Some(handle.id()), image);
+                names.insert(name.into(), index);
+            }
+            let (layout, _sources, atlas_image) = atlas_builder.build()?;
+            (layout, atlas_image)
+        };
+
+        Ok(Self {
+            image: images.add(atlas_image),
+            layout: layouts.add(layout),
+            names,
+            tiles: Vec::new(),
+        })
+    }
+
+    /// Names the tiles of the atlas: the name at position `i` refers to the tile `i` of the layout
+    pub fn with_tile_names<S: Into<String>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        for (index, name) in names.into_iter().enumerate() {
+            self.names.insert(name.into(), index);
+        }
+        self
+    }
+
+    /// Names the tile `index` of the atlas
+    pub fn with_tile_name(mut self, name: impl Into<String>, index: usize) -> Self {
+        self.names.insert(name.into(), index);
+        self
+    }
+
+    /// Adds a sprite of the atlas `tile` to the model `model_index`
+    pub fn add_tile(self, model_index: ModelIndex, tile: impl Into<AtlasTile>) -> Self {
+        self.add_tile_with_offsets(model_index, tile, GridDelta::new(0, 0, 0), Vec3::ZERO)
+    }
+
+    /// Adds a sprite of the atlas `tile` to the model `model_index`, with offsets from the node position (see [`ModelAsset`])
+    pub fn add_tile_with_offsets(
+        mut self,
+        model_index: ModelIndex,
+        tile: impl Into<AtlasTile>,
+        grid_offset: GridDelta,
+        world_offset: Vec3,
+    ) -> Self {
+        self.tiles
+            .push((model_index, None, tile.into(), grid_offset, world_offset));
+        self
+    }
+
+    /// Adds a sprite of the atlas `tile` to the model `model_index` when it is generated with the rotation `rotation`. The sprite is spawned without rotation, see [`ModelsAssets::add_rotated`].
+    pub fn add_rotated_tile(
+        mut self,
+        model_index: ModelIndex,
+        rotation: ModelRotation,
+        tile: impl Into<AtlasTile>,
+    ) -> Self {
+        self.tiles.push((
+            model_index,
+            Some(rotation),
+            tile.into(),
+            GridDelta::new(0, 0, 0),
+            Vec3::ZERO,
+        ));
+        self
+    }
+
+    /// Returns the atlas image handle
+    pub fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+
+    /// Returns the atlas layout handle
+    pub fn layout(&self) -> &Handle<TextureAtlasLayout> {
+        &self.layout
+    }
+
+    /// Returns the index of the tile named `name`, if any
+    pub fn tile_index(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// Builds the [`ModelsAssets`].
+    ///
+    /// Returns an [`UnknownAtlasTile`] error if a tile was referenced by a name which was not registered.
+    pub fn build(self) -> Result<ModelsAssets<Sprite>, UnknownAtlasTile> {
+        let mut models_assets = ModelsAssets::new();
+        for (model_index, rotation, tile, grid_offset, world_offset) in self.tiles.iter() {
+            let index = match tile {
+                AtlasTile::Index(index) => *index,
+                AtlasTile::Name(name) => self
+                    .tile_index(name)
+                    .ok_or_else(|| UnknownAtlasTile(name.clone()))?,
+            };
+            let model_asset = ModelAsset {
+                assets_bundle: Sprite::from_atlas_image(
+                    self.image.clone(),
+                    TextureAtlas {
+                        layout: self.layout.clone(),
+                        index,
+                    },
+                ),
+                spawn_hook: SpawnHook::none(),
+                grid_offset: *grid_offset,
+                world_offset: *world_offset,
+            };
+            match rotation {
+                Some(rotation) => models_assets.add_rotated(*model_index, *rotation, model_asset),
+                None => models_assets.add(*model_index, model_asset),
+            }
+        }
+        Ok(models_assets)
+    }
+}
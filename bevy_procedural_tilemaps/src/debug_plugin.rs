@@ -0,0 +1,361 @@
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+use bevy::{
+    app::{App, Plugin, PostUpdate, PreUpdate},
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Added, Changed, Or, Without},
+        resource::Resource,
+        schedule::{common_conditions::resource_exists, IntoScheduleConfigs},
+        system::{Commands, Query, Res},
+    },
+    gizmos::{config::GizmoConfigStore, gizmos::Gizmos},
+    math::{Isometry3d, Vec2, Vec3},
+    transform::components::GlobalTransform,
+};
+use procedural_tilemaps_core::{
+    generator::{observer::GenerationListener, Generator},
+    grid::{cartesian::grid::CartesianGrid, coordinate_system::CoordinateSystem, Grid},
+    GeneratorError, NodeIndex,
+};
+
+use crate::{
+    assets::BundleInserter,
+    layout::{CartesianLayout, NodeLayout},
+    spawner::NodesSpawner,
+};
+
+/// Plugin that draws a debug overlay over every generator: the grid outline, a heatmap of the nodes' remaining possibilities (or entropy) and a marker on the last contradiction.
+///
+/// The overlay is drawn with gizmos, using the `node_size` of the generator's [`NodesSpawner`]. Nodes are placed with the layout `L` of the generator entity, or the default one if it has none. The overlay is configured and toggled at runtime through the [`ProcGenDebugSettings`] resource.
+///
+/// The debug state ([`ProcGenDebugNodes`]) is only refreshed when its generator changes. It is updated even without a renderer, so it can be inspected headlessly:
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::{debug_plugin::ProcGenDebugNodes, prelude::*};
+///
+/// let mut sockets = SocketCollection::new();
+/// let a = sockets.create();
+/// sockets.add_connection(a, vec![a]);
+/// let mut models = ModelCollection::<Cartesian3D>::new();
+/// models.create(SocketsCartesian3D::Mono(a));
+/// models.create(SocketsCartesian3D::Mono(a));
+/// let rules = RulesBuilder::new_cartesian_3d(models, sockets).build().unwrap();
+/// let generator = GeneratorBuilder::new()
+///     .with_rules(rules)
+///     .with_grid(CartesianGrid::new_cartesian_3d(4, 4, 1, false, false, false))
+///     .build()
+///     .unwrap();
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     ProcGenDebugPlugin::<Cartesian3D, Handle<Image>>::default(),
+/// ));
+/// let entity = app.world_mut().spawn(generator).id();
+/// app.update();
+/// app.update();
+///
+/// let debug_nodes = app.world().get::<ProcGenDebugNodes>(entity).unwrap();
+/// assert_eq!(debug_nodes.possibilities, vec![2; 16]);
+/// assert_eq!(debug_nodes.contradiction, None);
+///
+/// // Nothing is recomputed until the generator changes
+/// app.world_mut().get_mut::<ProcGenDebugNodes>(entity).unwrap().possibilities[0] = 0;
+/// app.update();
+/// assert_eq!(app.world().get::<ProcGenDebugNodes>(entity).unwrap().possibilities[0], 0);
+/// app.world_mut()
+///     .get_mut::<Generator<Cartesian3D, CartesianGrid<Cartesian3D>>>(entity)
+///     .unwrap()
+///     .set_changed();
+/// app.update();
+/// assert_eq!(app.world().get::<ProcGenDebugNodes>(entity).unwrap().possibilities[0], 2);
+///
+/// app.world_mut().resource_mut::<ProcGenDebugSettings>().toggle();
+/// assert!(!app.world().resource::<ProcGenDebugSettings>().enabled);
+/// ```
+pub struct ProcGenDebugPlugin<
+    C: CoordinateSystem,
+    A: BundleInserter,
+    G: Grid<C> + Send + Sync + 'static = CartesianGrid<C>,
+    L: NodeLayout<C, G> = CartesianLayout,
+> {
+    typestate: PhantomData<(C, A, G, L)>,
+}
+
+impl<
+        C: CoordinateSystem,
+        A: BundleInserter,
+        G: Grid<C> + Send + Sync + 'static,
+        L: NodeLayout<C, G>,
+    > Default for ProcGenDebugPlugin<C, A, G, L>
+{
+    fn default() -> Self {
+        Self {
+            typestate: PhantomData,
+        }
+    }
+}
+
+impl<
+        C: CoordinateSystem,
+        A: BundleInserter,
+        G: Grid<C> + Send + Sync + 'static,
+        L: NodeLayout<C, G>,
+    > Plugin for ProcGenDebugPlugin<C, A, G, L>
+{
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProcGenDebugSettings>();
+        app.add_systems(PreUpdate, attach_debug_nodes::<C, G>);
+        app.add_systems(
+            PostUpdate,
+            (
+                update_debug_nodes::<C, G>,
+                draw_debug_nodes::<C, A, G, L>.run_if(resource_exists::<GizmoConfigStore>),
+            )
+                .chain()
+                .run_if(debug_enabled),
+        );
+    }
+}
+
+/// How the nodes of a generator are coloured by the debug overlay
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugColorMode {
+    /// Colour by the number of possible models remaining on the node
+    #[default]
+    Possibilities,
+    /// Colour by the Shannon entropy of the node, computed from the weights of its possible models
+    Entropy,
+}
+
+/// Runtime settings of the [`ProcGenDebugPlugin`]
+#[derive(Resource, Clone, Debug)]
+pub struct ProcGenDebugSettings {
+    /// Whether the debug state is updated and the overlay drawn
+    pub enabled: bool,
+    /// How the nodes are coloured
+    pub color_mode: DebugColorMode,
+    /// Colour of the grid outline
+    pub grid_color: Color,
+    /// Colour of the contradiction marker
+    pub contradiction_color: Color,
+}
+
+impl Default for ProcGenDebugSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            color_mode: DebugColorMode::default(),
+            grid_color: Color::srgba(1., 1., 1., 0.2),
+            contradiction_color: Color::srgb(1., 0., 1.),
+        }
+    }
+}
+
+impl ProcGenDebugSettings {
+    /// Enables the overlay if it was disabled, disables it otherwise
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+}
+
+/// Debug state of a generator, attached by the [`ProcGenDebugPlugin`] to every entity with a [`Generator`]
+#[derive(Component, Clone, Debug)]
+pub struct ProcGenDebugNodes {
+    /// Number of possible models remaining on each node
+    pub possibilities: Vec<usize>,
+    /// Entropy of each node, computed from the weights of its possible models
+    pub entropies: Vec<f32>,
+    /// Node of the last contradiction, as reported by [`GeneratorError`]. Cleared when the generator is reinitialized.
+    pub contradiction: Option<NodeIndex>,
+    recorder: Arc<Mutex<ContradictionRecorder>>,
+}
+
+impl ProcGenDebugNodes {
+    /// Marks the node reported by `error`, for a contradiction observed outside of the generator's listeners
+    pub fn mark_contradiction(&mut self, error: &GeneratorError) {
+        self.contradiction = Some(error.node_index);
+        if let Ok(mut recorder) = self.recorder.lock() {
+            recorder.contradiction = Some(error.node_index);
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+struct ContradictionRecorder {
+    contradiction: Option<NodeIndex>,
+}
+
+impl GenerationListener for ContradictionRecorder {
+    fn on_reinitialize(&mut self, _seed: u64) {
+        self.contradiction = None;
+    }
+
+    fn on_contradiction(&mut self, node_index: NodeIndex) {
+        self.contradiction = Some(node_index);
+    }
+}
+
+fn debug_enabled(settings: Res<ProcGenDebugSettings>) -> bool {
+    settings.enabled
+}
+
+/// Attaches a [`ProcGenDebugNodes`] to generators which don't have one yet, and registers the listener recording their contradictions.
+#[allow(clippy::type_complexity)]
+pub fn attach_debug_nodes<C: CoordinateSystem, G: Grid<C> + Send + Sync + 'static>(
+    mut commands: Commands,
+    mut generators: Query<(Entity, &mut Generator<C, G>), Without<ProcGenDebugNodes>>,
+) {
+    for (entity, mut generator) in generators.iter_mut() {
+        let recorder = Arc::new(Mutex::new(ContradictionRecorder::default()));
+        generator.add_listener(recorder.clone());
+        commands.entity(entity).insert(ProcGenDebugNodes {
+            possibilities: Vec::new(),
+            entropies: Vec::new(),
+            contradiction: None,
+            recorder,
+        });
+    }
+}
+
+/// Updates the possibilities, entropies and contradiction of the [`ProcGenDebugNodes`] which were just attached or whose [`Generator`] changed since the last update.
+#[allow(clippy::type_complexity)]
+pub fn update_debug_nodes<C: CoordinateSystem, G: Grid<C> + Send + Sync + 'static>(
+    mut generators: Query<
+        (&Generator<C, G>, &mut ProcGenDebugNodes),
+        Or<(Changed<Generator<C, G>>, Added<ProcGenDebugNodes>)>,
+    >,
+) {
+    for (generator, mut debug_nodes) in generators.iter_mut() {
+        let nodes_count = generator.grid().total_size();
+        debug_nodes.possibilities.clear();
+        debug_nodes.entropies.clear();
+        for node_index in 0..nodes_count {
+            debug_nodes
+                .possibilities
+                .push(generator.get_models_on(node_index).len());
+            debug_nodes
+                .entropies
+                .push(node_entropy(generator, node_index));
+        }
+        let recorded_contradiction = match debug_nodes.recorder.lock() {
+            Ok(recorder) => recorder.contradiction,
+            Err(_) => debug_nodes.contradiction,
+        };
+        debug_nodes.contradiction = recorded_contradiction;
+    }
+}
+
+/// Draws the grid outline, the nodes heatmap and the contradiction marker of every generator.
+#[allow(clippy::type_complexity)]
+pub fn draw_debug_nodes<
+    C: CoordinateSystem,
+    A: BundleInserter,
+    G: Grid<C> + Send + Sync + 'static,
+    L: NodeLayout<C, G>,
+>(
+    mut gizmos: Gizmos,
+    settings: Res<ProcGenDebugSettings>,
+    generators: Query<(
+        &Generator<C, G>,
+        &ProcGenDebugNodes,
+        &NodesSpawner<A>,
+        Option<&L>,
+        Option<&GlobalTransform>,
+    )>,
+) {
+    let default_layout = L::default();
+    for (generator, debug_nodes, spawner, layout, transform) in generators.iter() {
+        let layout = layout.unwrap_or(&default_layout);
+        let grid = generator.grid();
+        let transform = transform.copied().unwrap_or_default();
+        let node_size = spawner.node_size;
+        let rect_size = Vec2::new(node_size.x, node_size.y);
+
+        let max_value = match settings.color_mode {
+            DebugColorMode::Possibilities => (generator.rules().models_count() as f32 - 1.).max(0.),
+            DebugColorMode::Entropy => debug_nodes.entropies.iter().copied().fold(0., f32::max),
+        };
+
+        for node_index in 0..debug_nodes.possibilities.len() {
+            let isometry = node_isometry(grid, layout, spawner, &transform, node_index);
+            gizmos.rect(isometry, rect_size, settings.grid_color);
+
+            let possibilities = debug_nodes.possibilities[node_index];
+            let value = match settings.color_mode {
+                DebugColorMode::Possibilities => possibilities.saturating_sub(1) as f32,
+                DebugColorMode::Entropy => debug_nodes.entropies[node_index],
+            };
+            let heat = match max_value > 0. {
+                true => value / max_value,
+                false => 0.,
+            };
+            let color = match possibilities {
+                0 => settings.contradiction_color,
+                _ => heat_color(heat),
+            };
+            gizmos.rect(isometry, rect_size * 0.8, color);
+        }
+
+        if let Some(node_index) = debug_nodes.contradiction {
+            if node_index < grid.total_size() {
+                let isometry = node_isometry(grid, layout, spawner, &transform, node_index);
+                let half_size = 0.5 * rect_size.min_element();
+                gizmos.line(
+                    isometry * Vec3::new(-half_size, -half_size, 0.),
+                    isometry * Vec3::new(half_size, half_size, 0.),
+                    settings.contradiction_color,
+                );
+                gizmos.line(
+                    isometry * Vec3::new(-half_size, half_size, 0.),
+                    isometry * Vec3::new(half_size, -half_size, 0.),
+                    settings.contradiction_color,
+                );
+                gizmos.circle(isometry, half_size, settings.contradiction_color);
+            }
+        }
+    }
+}
+
+/// Center of the node in world space, placed by the `layout` like in [`crate::spawner::spawn_node`]
+fn node_isometry<C: CoordinateSystem, A: BundleInserter, G: Grid<C>, L: NodeLayout<C, G>>(
+    grid: &G,
+    layout: &L,
+    spawner: &NodesSpawner<A>,
+    transform: &GlobalTransform,
+    node_index: NodeIndex,
+) -> Isometry3d {
+    let node_transform = transform.mul_transform(layout.node_transform(grid, node_index, spawner));
+    Isometry3d::new(node_transform.translation(), node_transform.rotation())
+}
+
+/// Shannon entropy of the possible models on `node_index`
+fn node_entropy<C: CoordinateSystem, G: Grid<C>>(
+    generator: &Generator<C, G>,
+    node_index: NodeIndex,
+) -> f32 {
+    let mut weights_sum = 0.;
+    let mut weights_log_weights_sum = 0.;
+    for weight in generator.get_weights_on(node_index) {
+        if weight <= 0. {
+            continue;
+        }
+        weights_sum += weight;
+        weights_log_weights_sum += weight * weight.ln();
+    }
+    match weights_sum > 0. {
+        true => (weights_sum.ln() - weights_log_weights_sum / weights_sum).max(0.),
+        false => 0.,
+    }
+}
+
+/// From blue (0., few possibilities left) to red (1.)
+fn heat_color(heat: f32) -> Color {
+    Color::hsl(240. * (1. - heat.clamp(0., 1.)), 0.9, 0.5)
+}
@@ -12,6 +12,8 @@ use procedural_tilemaps_core::generator::model::ModelRotation;
 use super::assets::BundleInserter;
 
 /// **WARNING**: Assumes a rotation axis aligned with +Z (convenient for 2D sprites).
+///
+/// Tiles which cannot be rotated (for example with baked lighting or shadows) should have a pre-drawn image per rotation, see [`crate::assets::ModelsAssets::add_rotated`].
 impl BundleInserter for Handle<Image> {
     fn insert_bundle(
         &self,
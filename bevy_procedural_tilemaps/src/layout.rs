@@ -0,0 +1,491 @@
+use bevy::{
+    ecs::component::Component,
+    math::{Vec2, Vec3},
+    transform::components::Transform,
+};
+use procedural_tilemaps_core::{
+    grid::{
+        cartesian::{
+            coordinates::{CartesianCoordinates, CartesianPosition, GridDelta, WorldPosition},
+            grid::CartesianGrid,
+            masked::MaskedGrid,
+        },
+        coordinate_system::CoordinateSystem,
+        grid::Grid,
+        hexagonal::{coordinates::Hexagonal, grid::HexGrid},
+    },
+    NodeIndex,
+};
+
+use crate::{assets::BundleInserter, spawner::NodesSpawner};
+
+/// Maps the nodes of a grid `G` to world [`Transform`]s, to place the assets spawned for generated nodes.
+///
+/// A layout is read as a component on the generator entity. When the entity has none, the [`Default`] layout is used, so the default [`CartesianLayout`] does not need to be inserted.
+pub trait NodeLayout<C: CoordinateSystem, G: Grid<C>>: Component + Default {
+    /// Returns the world transform of the center of the node `node_index`, with the node size and settings of `spawner`.
+    ///
+    /// The spawned assets are placed relatively to this transform, see [`crate::spawner::spawn_node`].
+    fn node_transform<A: BundleInserter>(
+        &self,
+        grid: &G,
+        node_index: NodeIndex,
+        spawner: &NodesSpawner<A>,
+    ) -> Transform;
+
+    /// Returns the position of the node `node_index` in world grid units, given to the spawn hooks in [`crate::assets::NodeSpawnContext::world_position`].
+    fn world_position(&self, grid: &G, node_index: NodeIndex) -> WorldPosition;
+
+    /// Returns the translation, relative to the transform of the node `node_index`, of a point `grid_offset` nodes away from it. Places the assets with a [`crate::assets::ModelAsset::grid_offset`].
+    ///
+    /// By default, each unit of `grid_offset` moves by [`NodesSpawner::node_size`] along the matching world axis.
+    fn grid_offset_translation<A: BundleInserter>(
+        &self,
+        _grid: &G,
+        _node_index: NodeIndex,
+        spawner: &NodesSpawner<A>,
+        grid_offset: &GridDelta,
+    ) -> Vec3 {
+        spawner.node_size
+            * Vec3::new(
+                grid_offset.dx as f32,
+                grid_offset.dy as f32,
+                grid_offset.dz as f32,
+            )
+    }
+}
+
+/// Default layout of cartesian grids: nodes are boxes of size [`NodesSpawner::node_size`], placed at their world position (see [`CartesianGrid::origin`]).
+///
+/// The optional z offset of [`NodesSpawner::z_offset_from_y`] stays between `0` and `node_size.z`. By default, it is computed from the y position of the nodes within their grid, scaled by the y size of the grid, whatever the origin of the grid. To sort the nodes of several grids (such as chunks) together, set a [`NodesSpawner::z_offset_world_height`] covering all of them: the offset is then computed from the world y position of the nodes, scaled by this height and clamped.
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::prelude::*;
+///
+/// let spawner = NodesSpawner::new(ModelsAssets::<Handle<Image>>::new(), Vec3::ONE, Vec3::ONE)
+///     .with_z_offset_from_y(true)
+///     .with_z_offset_world_height(4000);
+/// let chunk = CartesianGrid::new_cartesian_2d(4, 4, false, false);
+/// let upper_chunk = chunk.clone().with_origin(WorldPosition::new_xy(0, 4));
+/// let far_chunk = chunk.clone().with_origin(WorldPosition::new_xy(0, 4000));
+///
+/// let depth = |grid: &CartesianGrid<Cartesian2D>, x, y| {
+///     let index = grid.index_from_coords(x, y, 0);
+///     CartesianLayout.node_transform(grid, index, &spawner).translation.z
+/// };
+/// // The top row of the lower chunk is in front of the bottom row of the upper chunk
+/// assert!(depth(&chunk, 0, 3) > depth(&upper_chunk, 0, 0));
+/// // The offset stays within one node depth, even far from the origin
+/// assert_eq!(depth(&far_chunk, 0, 3), 0.5);
+///
+/// // Without a world height, the rows of a grid are sorted within the grid, wherever it is placed
+/// let spawner = NodesSpawner::new(ModelsAssets::<Handle<Image>>::new(), Vec3::ONE, Vec3::ONE)
+///     .with_z_offset_from_y(true);
+/// let depth = |grid: &CartesianGrid<Cartesian2D>, x, y| {
+///     let index = grid.index_from_coords(x, y, 0);
+///     CartesianLayout.node_transform(grid, index, &spawner).translation.z
+/// };
+/// for origin_y in [-8, 0, 8] {
+///     let grid = chunk.clone().with_origin(WorldPosition::new_xy(0, origin_y));
+///     assert!(depth(&grid, 0, 0) > depth(&grid, 0, 1));
+///     assert!(depth(&grid, 0, 2) > depth(&grid, 0, 3));
+/// }
+/// ```
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct CartesianLayout;
+
+impl CartesianLayout {
+    fn cartesian_transform<C: CartesianCoordinates, A: BundleInserter>(
+        grid: &CartesianGrid<C>,
+        position: &CartesianPosition,
+        spawner: &NodesSpawner<A>,
+    ) -> Transform {
+        let world_position = grid.world_pos_from_pos(position);
+        let mut translation = spawner.node_size
+            * Vec3::new(
+                world_position.x as f32 + 0.5,
+                world_position.y as f32 + 0.5,
+                world_position.z as f32 + 0.5,
+            );
+        if spawner.z_offset_from_y {
+            let height_ratio = match spawner.z_offset_world_height {
+                Some(world_height) => (world_position.y as f32 / world_height as f32).clamp(0., 1.),
+                None => position.y as f32 / grid.size_y() as f32,
+            };
+            translation.z += spawner.node_size.z * (1. - height_ratio);
+        }
+        Transform::from_translation(translation)
+    }
+}
+
+impl<C: CartesianCoordinates> NodeLayout<C, CartesianGrid<C>> for CartesianLayout {
+    fn node_transform<A: BundleInserter>(
+        &self,
+        grid: &CartesianGrid<C>,
+        node_index: NodeIndex,
+        spawner: &NodesSpawner<A>,
+    ) -> Transform {
+        Self::cartesian_transform(grid, &grid.pos_from_index(node_index), spawner)
+    }
+
+    fn world_position(&self, grid: &CartesianGrid<C>, node_index: NodeIndex) -> WorldPosition {
+        grid.world_pos_from_index(node_index)
+    }
+}
+
+impl<C: CartesianCoordinates> NodeLayout<C, MaskedGrid<C>> for CartesianLayout {
+    fn node_transform<A: BundleInserter>(
+        &self,
+        grid: &MaskedGrid<C>,
+        node_index: NodeIndex,
+        spawner: &NodesSpawner<A>,
+    ) -> Transform {
+        Self::cartesian_transform(
+            grid.cartesian_grid(),
+            &grid.pos_from_index(node_index),
+            spawner,
+        )
+    }
+
+    fn world_position(&self, grid: &MaskedGrid<C>, node_index: NodeIndex) -> WorldPosition {
+        grid.cartesian_grid()
+            .world_pos_from_index(grid.full_index(node_index))
+    }
+}
+
+/// Layout of a [`HexGrid`] of pointy-top hexagons, in the XY plane.
+///
+/// [`NodesSpawner::node_size`] is the size of the bounding box of a hexagon: rows are `0.75 * node_size.y` apart, and odd rows are shifted by half a hexagon towards X+.
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::prelude::*;
+///
+/// let mut sockets = SocketCollection::new();
+/// let a = sockets.create();
+/// sockets.add_connection(a, vec![a]);
+/// let mut models = ModelCollection::<Hexagonal>::new();
+/// models.create(SocketsHexagonal::Mono(a));
+/// models.create(SocketsHexagonal::Mono(a));
+/// let rules = RulesBuilder::new_hexagonal(models, sockets).build().unwrap();
+/// let generator = GeneratorBuilder::new()
+///     .with_rules(rules)
+///     .with_grid(HexGrid::new(3, 2, false, false))
+///     .build()
+///     .unwrap();
+///
+/// let mut models_assets = ModelsAssets::<Handle<Image>>::new();
+/// for model_index in 0..2 {
+///     models_assets.add(model_index, ModelAsset {
+///         assets_bundle: Handle::default(),
+///         spawn_hook: SpawnHook::none(),
+///         grid_offset: GridDelta::new(0, 0, 0),
+///         world_offset: Vec3::ZERO,
+///     });
+/// }
+/// let spawner = NodesSpawner::new(models_assets, Vec3::new(2., 4., 1.), Vec3::ONE);
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     ProcGenSimplePlugin::<Hexagonal, Handle<Image>, HexGrid, HexLayout>::default(),
+/// ));
+/// let entity = app.world_mut().spawn((generator, spawner)).id();
+/// app.update();
+///
+/// let children = app.world().get::<Children>(entity).unwrap();
+/// // The first node of the second row is shifted by half a hexagon
+/// let transform = app.world().get::<Transform>(children[3]).unwrap();
+/// assert_eq!(transform.translation, Vec3::new(2., 5., 0.5));
+/// ```
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct HexLayout;
+
+impl HexLayout {
+    fn hex_translation<A: BundleInserter>(
+        x: i32,
+        y: i32,
+        z: i32,
+        spawner: &NodesSpawner<A>,
+    ) -> Vec3 {
+        let row_shift = if y.rem_euclid(2) == 1 { 0.5 } else { 0. };
+        spawner.node_size
+            * Vec3::new(
+                x as f32 + 0.5 + row_shift,
+                0.75 * y as f32 + 0.5,
+                z as f32 + 0.5,
+            )
+    }
+}
+
+impl NodeLayout<Hexagonal, HexGrid> for HexLayout {
+    fn node_transform<A: BundleInserter>(
+        &self,
+        grid: &HexGrid,
+        node_index: NodeIndex,
+        spawner: &NodesSpawner<A>,
+    ) -> Transform {
+        let position = grid.pos_from_index(node_index);
+        Transform::from_translation(Self::hex_translation(
+            position.x as i32,
+            position.y as i32,
+            0,
+            spawner,
+        ))
+    }
+
+    /// The offset coordinates of the hexagon, with a z of `0`.
+    fn world_position(&self, grid: &HexGrid, node_index: NodeIndex) -> WorldPosition {
+        let position = grid.pos_from_index(node_index);
+        WorldPosition::new(position.x as i32, position.y as i32, 0)
+    }
+
+    /// The x and y units of `grid_offset` are offset coordinates of the hexagons: the shift of odd rows depends on the row of the node.
+    fn grid_offset_translation<A: BundleInserter>(
+        &self,
+        grid: &HexGrid,
+        node_index: NodeIndex,
+        spawner: &NodesSpawner<A>,
+        grid_offset: &GridDelta,
+    ) -> Vec3 {
+        let position = grid.pos_from_index(node_index);
+        let (x, y) = (position.x as i32, position.y as i32);
+        Self::hex_translation(
+            x + grid_offset.dx,
+            y + grid_offset.dy,
+            grid_offset.dz,
+            spawner,
+        ) - Self::hex_translation(x, y, 0, spawner)
+    }
+}
+
+/// How an [`IsometricLayout`] arranges the nodes on screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IsometricProjection {
+    /// The axes of the grid run along the diagonals of the screen: X+ goes right and up, Y+ goes left and up. The map is a diamond.
+    #[default]
+    Diamond,
+    /// The rows of the grid zig-zag horizontally, odd rows being shifted by half a tile towards X+. Rows are half a tile apart and Y+ goes up. The map is a rectangle.
+    Staggered,
+}
+
+/// Layout of cartesian grids in isometric (or dimetric) projection, in the XY plane.
+///
+/// Tiles are diamonds of size `tile_size` on screen. Each z layer of the grid is raised by `layer_height`. The depth (z translation) of a node sorts the nodes from back to front: nodes lower on screen are drawn in front, and within the same row, nodes of higher layers are drawn in front.
+///
+/// This layout does not use [`NodesSpawner::node_size`] and [`NodesSpawner::z_offset_from_y`] to place the nodes.
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::prelude::*;
+///
+/// let layout = IsometricLayout::dimetric(64.);
+/// let grid = CartesianGrid::new_cartesian_3d(10, 10, 2, false, false, false);
+///
+/// let position = WorldPosition::new(3, 5, 1);
+/// let translation = layout.world_translation(&position);
+/// assert_eq!(translation.truncate(), Vec2::new(-64., 128. + 32.));
+///
+/// // Picking a point of the node on the same layer gives back the node
+/// let cursor = translation.truncate() + Vec2::new(10., 5.);
+/// assert_eq!(layout.pick_world_position(cursor, 1), position);
+/// let index = layout.pick_node(&grid, cursor, 1).unwrap();
+/// assert_eq!(grid.pos_from_index(index), CartesianPosition::new(3, 5, 1));
+///
+/// // Assets with a grid offset are moved along the axes of the projection
+/// let spawner = NodesSpawner::new(ModelsAssets::<Handle<Image>>::new(), Vec3::ONE, Vec3::ONE);
+/// let offset = layout.grid_offset_translation(&grid, index, &spawner, &GridDelta::new(1, 0, 0));
+/// assert_eq!(offset.truncate(), Vec2::new(32., 16.));
+/// ```
+#[derive(Component, Clone, Copy, Debug)]
+pub struct IsometricLayout {
+    /// Arrangement of the nodes
+    pub projection: IsometricProjection,
+    /// Width and height of a tile diamond on screen
+    pub tile_size: Vec2,
+    /// Vertical offset on screen between two z layers
+    pub layer_height: f32,
+    /// Depth difference between two consecutive rows on screen
+    pub depth_step: f32,
+    /// Depth difference between two z layers, within a row. Should be smaller than `depth_step` divided by the number of layers.
+    pub layer_depth: f32,
+}
+
+impl Default for IsometricLayout {
+    fn default() -> Self {
+        Self::dimetric(1.)
+    }
+}
+
+impl IsometricLayout {
+    /// Creates a new [`IsometricLayout`] of diamond-shaped tiles of size `tile_size`, with a layer height of `tile_size.y`
+    pub fn new(projection: IsometricProjection, tile_size: Vec2) -> Self {
+        Self {
+            projection,
+            tile_size,
+            layer_height: tile_size.y,
+            depth_step: 0.01,
+            layer_depth: 0.001,
+        }
+    }
+
+    /// Creates a [`IsometricProjection::Diamond`] layout with a true isometric ratio: tiles are `tile_width` wide and `tile_width / √3` high
+    pub fn diamond(tile_width: f32) -> Self {
+        Self::new(
+            IsometricProjection::Diamond,
+            Vec2::new(tile_width, tile_width / 3f32.sqrt()),
+        )
+    }
+
+    /// Creates a [`IsometricProjection::Staggered`] layout with a true isometric ratio: tiles are `tile_width` wide and `tile_width / √3` high
+    pub fn staggered(tile_width: f32) -> Self {
+        Self::new(
+            IsometricProjection::Staggered,
+            Vec2::new(tile_width, tile_width / 3f32.sqrt()),
+        )
+    }
+
+    /// Creates a [`IsometricProjection::Diamond`] layout with the 2:1 dimetric ratio common in pixel art: tiles are `tile_width` wide and `tile_width / 2` high
+    pub fn dimetric(tile_width: f32) -> Self {
+        Self::new(
+            IsometricProjection::Diamond,
+            Vec2::new(tile_width, tile_width / 2.),
+        )
+    }
+
+    /// Sets the projection
+    pub fn with_projection(mut self, projection: IsometricProjection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Sets the vertical offset on screen between two z layers
+    pub fn with_layer_height(mut self, layer_height: f32) -> Self {
+        self.layer_height = layer_height;
+        self
+    }
+
+    /// Sets the depth difference between two rows on screen, and between two z layers within a row
+    pub fn with_depth(mut self, depth_step: f32, layer_depth: f32) -> Self {
+        self.depth_step = depth_step;
+        self.layer_depth = layer_depth;
+        self
+    }
+
+    /// Returns the translation of the center of the node at `world_position`, with its depth as the z component
+    pub fn world_translation(&self, world_position: &WorldPosition) -> Vec3 {
+        let (x, y, z) = (world_position.x, world_position.y, world_position.z);
+        let half_tile = self.tile_size / 2.;
+        let (screen, row) = match self.projection {
+            IsometricProjection::Diamond => (
+                Vec2::new((x - y) as f32 * half_tile.x, (x + y) as f32 * half_tile.y),
+                x + y,
+            ),
+            IsometricProjection::Staggered => (
+                Vec2::new(
+                    x as f32 * self.tile_size.x + y.rem_euclid(2) as f32 * half_tile.x,
+                    y as f32 * half_tile.y,
+                ),
+                y,
+            ),
+        };
+        Vec3::new(
+            screen.x,
+            screen.y + z as f32 * self.layer_height,
+            z as f32 * self.layer_depth - row as f32 * self.depth_step,
+        )
+    }
+
+    /// Returns the translation from the node at `world_position` to the node `grid_offset` away from it, including the depth difference
+    fn offset_translation(&self, world_position: &WorldPosition, grid_offset: &GridDelta) -> Vec3 {
+        self.world_translation(&(*world_position + *grid_offset))
+            - self.world_translation(world_position)
+    }
+
+    /// Returns the world position of the node under the screen-space `point`, on the layer `z`. The inverse of [`IsometricLayout::world_translation`], for cursor picking.
+    pub fn pick_world_position(&self, point: Vec2, z: i32) -> WorldPosition {
+        let half_tile = self.tile_size / 2.;
+        // Coordinates along the diagonals of the screen, where tile centers are at integer positions
+        let a = point.x / half_tile.x;
+        let b = (point.y - z as f32 * self.layer_height) / half_tile.y;
+        let u = ((a + b) / 2.).round() as i32;
+        let v = ((b - a) / 2.).round() as i32;
+        match self.projection {
+            IsometricProjection::Diamond => WorldPosition::new(u, v, z),
+            IsometricProjection::Staggered => {
+                let y = u + v;
+                WorldPosition::new((u - v - y.rem_euclid(2)) / 2, y, z)
+            }
+        }
+    }
+
+    /// Returns the index of the node of `grid` under the screen-space `point`, on the layer `z` of the grid, or `None` if there is no such node.
+    pub fn pick_node<C: CartesianCoordinates>(
+        &self,
+        grid: &CartesianGrid<C>,
+        point: Vec2,
+        z: u32,
+    ) -> Option<NodeIndex> {
+        let world_position = self.pick_world_position(point, grid.origin().z + z as i32);
+        grid.try_index_from_world_pos(&world_position)
+    }
+}
+
+impl<C: CartesianCoordinates> NodeLayout<C, CartesianGrid<C>> for IsometricLayout {
+    fn node_transform<A: BundleInserter>(
+        &self,
+        grid: &CartesianGrid<C>,
+        node_index: NodeIndex,
+        _spawner: &NodesSpawner<A>,
+    ) -> Transform {
+        Transform::from_translation(self.world_translation(&grid.world_pos_from_index(node_index)))
+    }
+
+    fn world_position(&self, grid: &CartesianGrid<C>, node_index: NodeIndex) -> WorldPosition {
+        grid.world_pos_from_index(node_index)
+    }
+
+    fn grid_offset_translation<A: BundleInserter>(
+        &self,
+        grid: &CartesianGrid<C>,
+        node_index: NodeIndex,
+        _spawner: &NodesSpawner<A>,
+        grid_offset: &GridDelta,
+    ) -> Vec3 {
+        self.offset_translation(&grid.world_pos_from_index(node_index), grid_offset)
+    }
+}
+
+impl<C: CartesianCoordinates> NodeLayout<C, MaskedGrid<C>> for IsometricLayout {
+    fn node_transform<A: BundleInserter>(
+        &self,
+        grid: &MaskedGrid<C>,
+        node_index: NodeIndex,
+        _spawner: &NodesSpawner<A>,
+    ) -> Transform {
+        let cartesian_grid = grid.cartesian_grid();
+        let world_position = cartesian_grid.world_pos_from_pos(&grid.pos_from_index(node_index));
+        Transform::from_translation(self.world_translation(&world_position))
+    }
+
+    fn world_position(&self, grid: &MaskedGrid<C>, node_index: NodeIndex) -> WorldPosition {
+        grid.cartesian_grid()
+            .world_pos_from_index(grid.full_index(node_index))
+    }
+
+    fn grid_offset_translation<A: BundleInserter>(
+        &self,
+        grid: &MaskedGrid<C>,
+        node_index: NodeIndex,
+        _spawner: &NodesSpawner<A>,
+        grid_offset: &GridDelta,
+    ) -> Vec3 {
+        let cartesian_grid = grid.cartesian_grid();
+        let world_position = cartesian_grid.world_pos_from_pos(&grid.pos_from_index(node_index));
+        self.offset_translation(&world_position, grid_offset)
+    }
+}
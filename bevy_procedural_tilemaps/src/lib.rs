@@ -4,14 +4,31 @@
 
 /// Types to define and spawn assets tied to generated models.
 pub mod assets;
+/// Builds models assets from a texture atlas.
+#[cfg(feature = "default-bundle-inserters")]
+pub mod atlas;
+/// Debug overlay drawing the state of generators with gizmos.
+#[cfg(feature = "debug-plugin")]
+pub mod debug_plugin;
 /// Adds default bundle inserters for common Bevy asset handles.
 #[cfg(feature = "default-bundle-inserters")]
 pub mod default_bundles;
+/// Layouts mapping grid nodes to world transforms.
+pub mod layout;
 /// Convenience re-exports for Bevy integration.
 pub mod prelude;
+/// Loads generation rules from RON files as Bevy assets.
+#[cfg(feature = "rules-asset")]
+pub mod rules_asset;
 /// Lightweight Bevy plugin that runs generators and spawns their nodes.
 #[cfg(feature = "simple-plugin")]
 pub mod simple_plugin;
+/// Bevy plugin that runs generators step by step.
+#[cfg(feature = "stepper-plugin")]
+pub mod stepper;
+/// Renders generated grids with Bevy's tilemap chunks.
+#[cfg(feature = "tilemap-chunks")]
+pub mod tilemap;
 /// Components used to spawn generated nodes inside a Bevy world.
 pub mod spawner;
 
@@ -1,7 +1,23 @@
 //! Commonly used Bevy helpers re-exported for convenience.
 
-pub use crate::assets::{BundleInserter, ModelAsset, ModelsAssets};
+pub use crate::assets::{BundleInserter, ModelAsset, ModelsAssets, NodeSpawnHook, SpawnHook};
+#[cfg(feature = "default-bundle-inserters")]
+pub use crate::atlas::{AtlasAssetsBuilder, AtlasTile};
+#[cfg(feature = "debug-plugin")]
+pub use crate::debug_plugin::{DebugColorMode, ProcGenDebugPlugin, ProcGenDebugSettings};
+pub use crate::layout::{
+    CartesianLayout, HexLayout, IsometricLayout, IsometricProjection, NodeLayout,
+};
 pub use crate::proc_gen::prelude::*;
+#[cfg(feature = "rules-asset")]
+pub use crate::rules_asset::{ProcGenRulesAssetPlugin, RulesAsset, RulesAssetGenerator};
 #[cfg(feature = "simple-plugin")]
-pub use crate::simple_plugin::ProcGenSimplePlugin;
+pub use crate::simple_plugin::{
+    FailedGeneration, GenerationFailed, GenerationStarted, GenerationSucceeded,
+    ProcGenSimplePlugin, Regenerate, SpawnMode,
+};
 pub use crate::spawner::NodesSpawner;
+#[cfg(feature = "stepper-plugin")]
+pub use crate::stepper::{GenerationStepper, ProcGenStepperPlugin, StepperCommand, StepperMode};
+#[cfg(feature = "tilemap-chunks")]
+pub use crate::tilemap::{ProcGenTilemapPlugin, TilemapChunksSpawner, TilesetIndices};
@@ -0,0 +1,401 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use bevy::{
+    app::{App, Plugin, PreUpdate},
+    asset::{
+        io::Reader, Asset, AssetApp, AssetEvent, AssetLoader, AssetServer, Assets, Handle,
+        LoadContext,
+    },
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        entity::Entity,
+        message::MessageReader,
+        query::Has,
+        system::{Commands, Query, Res},
+        world::Ref,
+    },
+    image::Image,
+    log::error,
+    math::Vec3,
+    reflect::TypePath,
+};
+use procedural_tilemaps_core::{
+    generator::{
+        builder::GeneratorBuilder,
+        rules_definition::{RulesDefinition, RulesDefinitionCoordinates},
+        Generator, RngMode,
+    },
+    grid::cartesian::{
+        coordinates::{CartesianCoordinates, GridDelta},
+        grid::CartesianGrid,
+    },
+    GeneratorBuilderError,
+};
+use serde::Deserialize;
+
+use crate::{
+    assets::{BundleInserter, ModelAsset, ModelsAssets, SpawnHook},
+    spawner::NodesSpawner,
+};
+
+/// Plugin that loads [`RulesAsset`]s from `.rules.ron` files and builds a [`Generator`] and a [`NodesSpawner`] on every entity with a [`RulesAssetGenerator`].
+///
+/// When a rules file changes, the generators built from it are rebuilt: their nodes are despawned and the new [`Generator`] is picked up by the plugin running the generations, such as [`crate::simple_plugin::ProcGenSimplePlugin`] with `Handle<Image>` assets.
+///
+/// Changes are only detected with Bevy's asset hot-reloading, which requires enabling the `file_watcher` feature of `bevy` in your own `Cargo.toml` (this crate does not enable it). It is then turned on by default, and can be toggled with [`bevy::asset::AssetPlugin::watch_for_changes_override`].
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::prelude::*;
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     AssetPlugin::default(),
+///     ProcGenRulesAssetPlugin::<Cartesian3D>::default(),
+///     ProcGenSimplePlugin::<Cartesian3D, Handle<Image>>::default(),
+/// ));
+///
+/// let rules_asset = RulesAsset::from_ron(
+///     r#"(
+///         sockets: ["ground"],
+///         connections: [("ground", ["ground"])],
+///         models: [(sockets: Mono("ground")), (sockets: Mono("ground"), weight: 0.5)],
+///     )"#,
+/// )
+/// .unwrap();
+/// let handle = app
+///     .world_mut()
+///     .resource_mut::<Assets<RulesAsset>>()
+///     .add(rules_asset);
+/// let grid = CartesianGrid::new_cartesian_3d(8, 8, 1, false, false, false);
+/// let entity = app
+///     .world_mut()
+///     .spawn(RulesAssetGenerator::new(handle.clone(), grid))
+///     .id();
+///
+/// app.update();
+/// app.update();
+/// assert!(app.world().get::<Generator<Cartesian3D, CartesianGrid<Cartesian3D>>>(entity).is_some());
+/// assert!(app.world().get::<NodesSpawner<Handle<Image>>>(entity).is_some());
+///
+/// // Modifying the rules rebuilds the generator
+/// app.world_mut()
+///     .resource_mut::<Assets<RulesAsset>>()
+///     .get_mut(&handle)
+///     .unwrap()
+///     .models
+///     .pop();
+/// app.update();
+/// app.update();
+/// let generator = app.world().get::<Generator<Cartesian3D, CartesianGrid<Cartesian3D>>>(entity).unwrap();
+/// assert_eq!(generator.rules().original_models_count(), 1);
+/// let succeeded = app.world().resource::<Messages<GenerationSucceeded>>();
+/// assert!(succeeded.iter_current_update_messages().any(|message| message.entity == entity));
+/// ```
+pub struct ProcGenRulesAssetPlugin<C: RulesDefinitionCoordinates> {
+    typestate: PhantomData<C>,
+}
+
+impl<C: RulesDefinitionCoordinates> Default for ProcGenRulesAssetPlugin<C> {
+    fn default() -> Self {
+        Self {
+            typestate: PhantomData,
+        }
+    }
+}
+
+impl<C: RulesDefinitionCoordinates> Plugin for ProcGenRulesAssetPlugin<C> {
+    fn build(&self, app: &mut App) {
+        // The asset may already be registered by the plugin of another coordinate system
+        if !app.world().contains_resource::<Assets<RulesAsset>>() {
+            app.init_asset::<RulesAsset>();
+            app.register_asset_loader(RulesAssetLoader);
+        }
+        app.add_systems(PreUpdate, build_generators_from_rules_assets::<C>);
+    }
+}
+
+/// Rules of a generation, as defined in a RON file and loaded by the [`RulesAssetLoader`].
+///
+/// Wraps a [`RulesDefinition`] of the core crate, which builds the [`procedural_tilemaps_core::generator::rules::Rules`], with the assets paths of each model. Sockets are referenced by name. The models of the file are created in order, so the model at position `i` in `models` has the [`procedural_tilemaps_core::generator::model::ModelIndex`] `i`.
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::prelude::*;
+///
+/// let rules_asset = RulesAsset::from_ron(
+///     r#"(
+///         sockets: ["grass", "water", "shore"],
+///         connections: [
+///             ("grass", ["grass", "shore"]),
+///             ("water", ["water", "shore"]),
+///         ],
+///         models: [
+///             (name: "grass", sockets: Mono("grass"), weight: 2.0, assets: [(path: "grass.png")]),
+///             (
+///                 name: "water",
+///                 sockets: Mono("water"),
+///                 tags: ["water", "blocking"],
+///                 metadata: {"depth": "2"},
+///                 assets: [(path: "water.png")],
+///             ),
+///             (
+///                 name: "shore",
+///                 sockets: Simple(x_pos: "shore", x_neg: "shore", y_pos: "grass", y_neg: "water"),
+///                 rotations: All,
+///                 assets: [(path: "shore.png", world_offset: (0.0, 0.0, 1.0))],
+///             ),
+///         ],
+///     )"#,
+/// )
+/// .unwrap();
+///
+/// let rules = rules_asset.rules::<Cartesian2D>().unwrap();
+/// assert_eq!(rules.original_models_count(), 3);
+/// assert_eq!(rules.models_count(), 6);
+/// assert_eq!(rules.models_with_tag("water"), vec![1]);
+/// assert_eq!(rules.model_metadata(1).unwrap().get("depth"), Some("2"));
+///
+/// let models_assets = rules_asset.models_assets(|_path| Handle::<Image>::default());
+/// assert_eq!(models_assets[&2][0].world_offset, Vec3::new(0., 0., 1.));
+///
+/// // Cartesian3D models need sockets for the z axis
+/// assert!(rules_asset.rules::<Cartesian3D>().is_err());
+/// ```
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct RulesAsset(pub RulesDefinition);
+
+impl Deref for RulesAsset {
+    type Target = RulesDefinition;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for RulesAsset {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl RulesAsset {
+    /// Parses a [`RulesAsset`] from a RON string. `Option` fields can be given without `Some(...)`.
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron_options().from_str(ron)
+    }
+
+    /// Builds the [`ModelsAssets`] defined by this asset, creating each asset from its path with `load` (for example with [`AssetServer::load`])
+    pub fn models_assets<A: BundleInserter>(
+        &self,
+        mut load: impl FnMut(&str) -> A,
+    ) -> ModelsAssets<A> {
+        let mut models_assets = ModelsAssets::new();
+        for (model_index, definition) in self.models.iter().enumerate() {
+            for asset in definition.assets.iter() {
+                let (x, y, z) = asset.grid_offset;
+                models_assets.add(
+                    model_index,
+                    ModelAsset {
+                        assets_bundle: load(&asset.path),
+                        spawn_hook: SpawnHook::none(),
+                        grid_offset: GridDelta::new(x, y, z),
+                        world_offset: Vec3::from(asset.world_offset),
+                    },
+                );
+            }
+        }
+        models_assets
+    }
+}
+
+fn ron_options() -> ron::Options {
+    ron::Options::default().with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+}
+
+/// Errors returned by the [`RulesAssetLoader`]
+#[derive(Debug)]
+pub enum RulesAssetLoaderError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The file is not a valid [`RulesAsset`]
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for RulesAssetLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesAssetLoaderError::Io(err) => write!(f, "Could not read rules file: {}", err),
+            RulesAssetLoaderError::Ron(err) => write!(f, "Could not parse rules file: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RulesAssetLoaderError {}
+
+/// Loads [`RulesAsset`]s from `.rules.ron` files
+#[derive(Default, TypePath)]
+pub struct RulesAssetLoader;
+
+impl AssetLoader for RulesAssetLoader {
+    type Asset = RulesAsset;
+    type Settings = ();
+    type Error = RulesAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(RulesAssetLoaderError::Io)?;
+        ron_options()
+            .from_bytes(&bytes)
+            .map_err(RulesAssetLoaderError::Ron)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["rules.ron"]
+    }
+}
+
+/// Component referencing a [`RulesAsset`] by [`Handle`], from which the [`ProcGenRulesAssetPlugin`] builds a [`Generator`] and a [`NodesSpawner`] of `Handle<Image>` on the same entity.
+#[derive(Component, Clone)]
+pub struct RulesAssetGenerator<C: CartesianCoordinates> {
+    /// Handle to the rules
+    pub rules: Handle<RulesAsset>,
+    /// Grid of the generator
+    pub grid: CartesianGrid<C>,
+    /// Random number generator mode of the generator
+    pub rng_mode: RngMode,
+    /// Size of a node in world units, see [`NodesSpawner::node_size`]
+    pub node_size: Vec3,
+    /// Scale applied to spawned assets, see [`NodesSpawner::spawn_scale`]
+    pub spawn_scale: Vec3,
+    /// See [`NodesSpawner::z_offset_from_y`]
+    pub z_offset_from_y: bool,
+}
+
+impl<C: CartesianCoordinates> RulesAssetGenerator<C> {
+    /// Creates a new [`RulesAssetGenerator`] with a random seed, a node size and a spawn scale of [`Vec3::ONE`].
+    pub fn new(rules: Handle<RulesAsset>, grid: CartesianGrid<C>) -> Self {
+        Self {
+            rules,
+            grid,
+            rng_mode: RngMode::RandomSeed,
+            node_size: Vec3::ONE,
+            spawn_scale: Vec3::ONE,
+            z_offset_from_y: false,
+        }
+    }
+
+    /// Sets the random number generator mode of the generator
+    pub fn with_rng(mut self, rng_mode: RngMode) -> Self {
+        self.rng_mode = rng_mode;
+        self
+    }
+
+    /// Sets the node size and the spawn scale of the spawned assets
+    pub fn with_spawn_sizes(mut self, node_size: Vec3, spawn_scale: Vec3) -> Self {
+        self.node_size = node_size;
+        self.spawn_scale = spawn_scale;
+        self
+    }
+
+    /// Enables or disables the z offset based on the node's y coordinate
+    pub fn with_z_offset_from_y(mut self, z_offset_from_y: bool) -> Self {
+        self.z_offset_from_y = z_offset_from_y;
+        self
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn build(
+        &self,
+        rules_asset: &RulesAsset,
+        asset_server: &AssetServer,
+    ) -> Result<(Generator<C, CartesianGrid<C>>, NodesSpawner<Handle<Image>>), String>
+    where
+        C: RulesDefinitionCoordinates,
+    {
+        let rules = rules_asset.rules::<C>().map_err(|err| err.to_string())?;
+        let generator = GeneratorBuilder::new()
+            .with_rules(rules)
+            .with_grid(self.grid.clone())
+            .with_rng(self.rng_mode)
+            .build()
+            .map_err(|err: GeneratorBuilderError| err.to_string())?;
+        let models_assets = rules_asset.models_assets(|path| asset_server.load(path.to_string()));
+        let spawner = NodesSpawner::new(models_assets, self.node_size, self.spawn_scale)
+            .with_z_offset_from_y(self.z_offset_from_y)
+            .with_rotation_axis(generator.rules().rotation_axis());
+        Ok((generator, spawner))
+    }
+}
+
+/// Builds the [`Generator`] and [`NodesSpawner`] of new [`RulesAssetGenerator`]s once their rules are loaded, and rebuilds them when their rules or the [`RulesAssetGenerator`] itself are modified.
+///
+/// The previous [`Generator`] is removed before inserting the new one, so that the generation plugins register it as a new generator. Previously spawned nodes are despawned.
+#[allow(clippy::type_complexity)]
+pub fn build_generators_from_rules_assets<C: RulesDefinitionCoordinates>(
+    mut commands: Commands,
+    mut asset_events: MessageReader<AssetEvent<RulesAsset>>,
+    rules_assets: Res<Assets<RulesAsset>>,
+    asset_server: Res<AssetServer>,
+    rules_generators: Query<(
+        Entity,
+        Ref<RulesAssetGenerator<C>>,
+        Has<Generator<C, CartesianGrid<C>>>,
+    )>,
+) {
+    let mut changed_rules = HashSet::new();
+    for event in asset_events.read() {
+        match event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id }
+            | AssetEvent::LoadedWithDependencies { id } => {
+                changed_rules.insert(*id);
+            }
+            AssetEvent::Removed { .. } | AssetEvent::Unused { .. } => (),
+        }
+    }
+
+    for (entity, rules_generator, has_generator) in rules_generators.iter() {
+        if !rules_generator.is_changed() && !changed_rules.contains(&rules_generator.rules.id()) {
+            continue;
+        }
+        let Some(rules_asset) = rules_assets.get(&rules_generator.rules) else {
+            continue;
+        };
+        match rules_generator.build(rules_asset, &asset_server) {
+            Ok((generator, spawner)) => {
+                let mut entity_commands = commands.entity(entity);
+                if has_generator {
+                    entity_commands
+                        .remove::<Generator<C, CartesianGrid<C>>>()
+                        .despawn_children();
+                    #[cfg(feature = "simple-plugin")]
+                    entity_commands.remove::<crate::simple_plugin::FailedGeneration>();
+                }
+                entity_commands.insert((generator, spawner));
+            }
+            Err(err) => error!(
+                "Could not build the generator of entity {} from its rules asset: {}",
+                entity, err
+            ),
+        }
+    }
+}
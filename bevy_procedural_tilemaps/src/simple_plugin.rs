@@ -1,106 +1,714 @@
-use std::marker::PhantomData;
+use std::{collections::HashMap, marker::PhantomData};
 
 use bevy::{
     app::{App, Plugin, Update},
     ecs::{
+        component::Component,
         entity::Entity,
-        query::Added,
+        message::{Message, MessageWriter},
+        query::{Added, QueryData, With},
         schedule::IntoScheduleConfigs,
         system::{Commands, Query, ResMut},
     },
-    prelude::{Children, Resource},
+    prelude::Resource,
 };
 use procedural_tilemaps_core::{
-    generator::Generator,
-    grid::cartesian::{coordinates::CartesianCoordinates, grid::CartesianGrid},
+    generator::{
+        model::ModelInstance,
+        observer::{GenerationUpdate, QueuedObserver},
+        GenerationStatus, Generator,
+    },
+    grid::{cartesian::grid::CartesianGrid, coordinate_system::CoordinateSystem, Grid, GridData},
     GeneratorError,
 };
 
 use crate::{
     assets::BundleInserter,
+    layout::{CartesianLayout, NodeLayout},
     spawner::{spawn_node, NodesSpawner},
 };
 
 /// Plugin that runs every generator once per frame until a valid grid is produced,
 /// then spawns the generated nodes using the attached [`NodesSpawner`].
-pub struct ProcGenSimplePlugin<C: CartesianCoordinates, A: BundleInserter> {
-    typestate: PhantomData<(C, A)>,
+///
+/// With [`SpawnMode::Incremental`], nodes are instead spawned as soon as they are generated.
+///
+/// The lifecycle of each generation is reported with the [`GenerationStarted`], [`GenerationSucceeded`] and [`GenerationFailed`] messages. By default, a failed generation is retried on the next frame forever. With [`ProcGenSimplePlugin::with_max_frame_retries`], a generator which keeps failing is given up and marked with a [`FailedGeneration`] component.
+///
+/// Several instances of the plugin, for different coordinate systems, grids or assets, can run side by side with their own settings.
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::{prelude::*, simple_plugin::SpawnMode};
+///
+/// let mut sockets = SocketCollection::new();
+/// let a = sockets.create();
+/// sockets.add_connection(a, vec![a]);
+/// let mut models = ModelCollection::<Cartesian3D>::new();
+/// models.create(SocketsCartesian3D::Mono(a));
+/// models.create(SocketsCartesian3D::Mono(a));
+/// let rules = RulesBuilder::new_cartesian_3d(models, sockets.clone()).build().unwrap();
+/// let cartesian_generator = GeneratorBuilder::new()
+///     .with_rules(rules)
+///     .with_grid(CartesianGrid::new_cartesian_3d(4, 4, 1, false, false, false))
+///     .build()
+///     .unwrap();
+/// let mut models = ModelCollection::<Hexagonal>::new();
+/// models.create(SocketsHexagonal::Mono(a));
+/// models.create(SocketsHexagonal::Mono(a));
+/// let rules = RulesBuilder::new_hexagonal(models, sockets).build().unwrap();
+/// let hex_generator = GeneratorBuilder::new()
+///     .with_rules(rules)
+///     .with_grid(HexGrid::new(3, 2, false, false))
+///     .build()
+///     .unwrap();
+///
+/// let mut models_assets = ModelsAssets::<Handle<Image>>::new();
+/// for model_index in 0..2 {
+///     models_assets.add(model_index, ModelAsset {
+///         assets_bundle: Handle::default(),
+///         spawn_hook: SpawnHook::none(),
+///         grid_offset: GridDelta::new(0, 0, 0),
+///         world_offset: Vec3::ZERO,
+///     });
+/// }
+/// let spawner = NodesSpawner::new(models_assets, Vec3::ONE, Vec3::ONE);
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     ProcGenSimplePlugin::<Cartesian3D, Handle<Image>>::new(SpawnMode::Incremental {
+///         steps_per_frame: Some(4),
+///     }),
+///     ProcGenSimplePlugin::<Hexagonal, Handle<Image>, HexGrid, HexLayout>::default(),
+/// ));
+/// let cartesian_entity = app.world_mut().spawn((cartesian_generator, spawner.clone())).id();
+/// let hex_entity = app.world_mut().spawn((hex_generator, spawner)).id();
+///
+/// let nodes_count = |app: &App, entity| app.world().get::<Children>(entity).map_or(0, |c| c.len());
+/// app.update();
+/// assert_eq!(nodes_count(&app, cartesian_entity), 4);
+/// assert_eq!(nodes_count(&app, hex_entity), 6);
+/// for _ in 0..3 {
+///     app.update();
+/// }
+/// assert_eq!(nodes_count(&app, cartesian_entity), 16);
+/// ```
+pub struct ProcGenSimplePlugin<
+    C: CoordinateSystem,
+    A: BundleInserter,
+    G: Grid<C> + Send + Sync + 'static = CartesianGrid<C>,
+    L: NodeLayout<C, G> = CartesianLayout,
+> {
+    mode: SpawnMode,
+    max_frame_retries: Option<u32>,
+    typestate: PhantomData<(C, A, G, L)>,
 }
 
-impl<C: CartesianCoordinates, A: BundleInserter> Default for ProcGenSimplePlugin<C, A> {
+impl<
+        C: CoordinateSystem,
+        A: BundleInserter,
+        G: Grid<C> + Send + Sync + 'static,
+        L: NodeLayout<C, G>,
+    > Default for ProcGenSimplePlugin<C, A, G, L>
+{
     fn default() -> Self {
+        Self::new(SpawnMode::default())
+    }
+}
+
+impl<
+        C: CoordinateSystem,
+        A: BundleInserter,
+        G: Grid<C> + Send + Sync + 'static,
+        L: NodeLayout<C, G>,
+    > ProcGenSimplePlugin<C, A, G, L>
+{
+    /// Creates a new [`ProcGenSimplePlugin`] spawning the generated nodes according to `mode`
+    pub fn new(mode: SpawnMode) -> Self {
         Self {
+            mode,
+            max_frame_retries: None,
             typestate: PhantomData,
         }
     }
+
+    /// Sets how many frames a failed generation is retried before the generator is marked with a [`FailedGeneration`] component. Unlimited by default.
+    pub fn with_max_frame_retries(mut self, max_frame_retries: u32) -> Self {
+        self.max_frame_retries = Some(max_frame_retries);
+        self
+    }
 }
 
-impl<C: CartesianCoordinates, A: BundleInserter> Plugin for ProcGenSimplePlugin<C, A> {
+impl<
+        C: CoordinateSystem,
+        A: BundleInserter,
+        G: Grid<C> + Send + Sync + 'static,
+        L: NodeLayout<C, G>,
+    > Plugin for ProcGenSimplePlugin<C, A, G, L>
+{
     fn build(&self, app: &mut App) {
-        app.insert_resource(PendingGenerations::default());
-        app.add_systems(
-            Update,
-            (register_new_generations::<C>, generate_and_spawn::<C, A>).chain(),
-        );
+        let steps_per_frame = match self.mode {
+            SpawnMode::OnCompletion => None,
+            SpawnMode::Incremental { steps_per_frame } => steps_per_frame,
+        };
+        app.insert_resource(PendingGenerations::<C, G, NodesSpawner<A>>::new(
+            self.max_frame_retries,
+            steps_per_frame,
+        ));
+        app.add_message::<GenerationStarted>();
+        app.add_message::<GenerationSucceeded>();
+        app.add_message::<GenerationFailed>();
+        match self.mode {
+            SpawnMode::OnCompletion => {
+                app.add_systems(
+                    Update,
+                    (
+                        register_new_generations::<C, G, NodesSpawner<A>>,
+                        regenerate_generations::<C, G, NodesSpawner<A>>,
+                        generate_and_spawn::<C, A, G, L>,
+                    )
+                        .chain(),
+                );
+            }
+            SpawnMode::Incremental { .. } => {
+                app.add_systems(
+                    Update,
+                    (
+                        register_new_incremental_generations::<C, A, G, L>,
+                        regenerate_generations::<C, G, NodesSpawner<A>>,
+                        generate_incrementally::<C, A, G>,
+                        spawn_generated_nodes::<C, A, G, L>,
+                    )
+                        .chain(),
+                );
+            }
+        }
+    }
+}
+
+/// When the [`ProcGenSimplePlugin`] spawns the generated nodes
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::{prelude::*, simple_plugin::SpawnMode};
+///
+/// let mut sockets = SocketCollection::new();
+/// let a = sockets.create();
+/// sockets.add_connection(a, vec![a]);
+/// let mut models = ModelCollection::<Cartesian3D>::new();
+/// models.create(SocketsCartesian3D::Mono(a));
+/// models.create(SocketsCartesian3D::Mono(a));
+/// let rules = RulesBuilder::new_cartesian_3d(models, sockets).build().unwrap();
+/// let generator = GeneratorBuilder::new()
+///     .with_rules(rules)
+///     .with_grid(CartesianGrid::new_cartesian_3d(4, 4, 1, false, false, false))
+///     .build()
+///     .unwrap();
+///
+/// let mut models_assets = ModelsAssets::<Handle<Image>>::new();
+/// for model_index in 0..2 {
+///     models_assets.add(model_index, ModelAsset {
+///         assets_bundle: Handle::default(),
+///         spawn_hook: SpawnHook::none(),
+///         grid_offset: GridDelta::new(0, 0, 0),
+///         world_offset: Vec3::ZERO,
+///     });
+/// }
+/// let spawner = NodesSpawner::new(models_assets, Vec3::ONE, Vec3::ONE);
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     ProcGenSimplePlugin::<Cartesian3D, Handle<Image>>::new(SpawnMode::Incremental {
+///         steps_per_frame: Some(4),
+///     }),
+/// ));
+/// let entity = app.world_mut().spawn((generator, spawner)).id();
+///
+/// // The map fills in over a few frames
+/// let nodes_count = |app: &App| app.world().get::<Children>(entity).map_or(0, |c| c.len());
+/// app.update();
+/// assert_eq!(nodes_count(&app), 4);
+/// for _ in 0..3 {
+///     app.update();
+/// }
+/// assert_eq!(nodes_count(&app), 16);
+/// ```
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnMode {
+    /// Nodes are spawned once the whole grid is successfully generated
+    #[default]
+    OnCompletion,
+    /// Nodes are spawned as soon as they are generated, from the [`GenerationUpdate`]s of a [`QueuedObserver`] attached to each generator. All the nodes are despawned when the generator reinitializes.
+    ///
+    /// The generation advances by at most `steps_per_frame` steps (see [`Generator::select_and_propagate`]) each frame, or until it is done or failed if [`None`]. A failed generation is reinitialized on the next frame, so that its partial result stays visible for one frame.
+    Incremental {
+        /// Maximum number of generation steps per frame
+        steps_per_frame: Option<u32>,
+    },
+}
+
+/// Message written by the [`ProcGenSimplePlugin`] when it starts running a new generator
+#[derive(Message, Clone, Copy, Debug)]
+pub struct GenerationStarted {
+    /// Entity of the [`Generator`]
+    pub entity: Entity,
+}
+
+/// Message written by the [`ProcGenSimplePlugin`] when a generation succeeds
+#[derive(Message, Clone, Copy, Debug)]
+pub struct GenerationSucceeded {
+    /// Entity of the [`Generator`]
+    pub entity: Entity,
+    /// Seed of the successful generation
+    pub seed: u64,
+    /// Number of tries needed to generate the grid, over all the frames
+    pub try_count: u32,
+}
+
+/// Message written by the [`ProcGenSimplePlugin`] (and the `ProcGenStepperPlugin`) when a generation fails
+#[derive(Message, Clone, Copy, Debug)]
+pub struct GenerationFailed {
+    /// Entity of the [`Generator`]
+    pub entity: Entity,
+    /// Contradiction which made the generation fail
+    pub error: GeneratorError,
+    /// Whether the generation will be retried on the next frame. If `false`, the generator was marked with a [`FailedGeneration`] component.
+    pub will_retry: bool,
+}
+
+/// Component inserted by the [`ProcGenSimplePlugin`] (and the `ProcGenStepperPlugin`) on a generator which failed more than the maximum number of frame retries.
+///
+/// The generator is not run anymore. Insert a [`Regenerate`] component on the entity to reinitialize its generator and restart it, which also removes this component (with the `ProcGenStepperPlugin`, send a `StepperCommand::Reinitialize` instead).
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FailedGeneration {
+    /// Contradiction which made the last try fail
+    pub error: GeneratorError,
+}
+
+/// Component requesting the [`ProcGenSimplePlugin`] to reroll the generator on the same entity.
+///
+/// The generator is reinitialized, with `seed` if specified or else with its next seed, and queued again. The component is removed once handled.
+///
+/// Previously spawned nodes are despawned when the new grid is spawned (or, with [`SpawnMode::Incremental`], right away).
+///
+/// ### Example
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::prelude::*;
+///
+/// let mut sockets = SocketCollection::new();
+/// let a = sockets.create();
+/// sockets.add_connection(a, vec![a]);
+/// let mut models = ModelCollection::<Cartesian3D>::new();
+/// models.create(SocketsCartesian3D::Mono(a));
+/// models.create(SocketsCartesian3D::Mono(a));
+/// let rules = RulesBuilder::new_cartesian_3d(models, sockets).build().unwrap();
+/// let generator = GeneratorBuilder::new()
+///     .with_rules(rules)
+///     .with_grid(CartesianGrid::new_cartesian_3d(4, 4, 1, false, false, false))
+///     .build()
+///     .unwrap();
+/// let spawner = NodesSpawner::new(ModelsAssets::<Handle<Image>>::new(), Vec3::ONE, Vec3::ONE);
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     ProcGenSimplePlugin::<Cartesian3D, Handle<Image>>::default(),
+/// ));
+/// let entity = app.world_mut().spawn((generator, spawner)).id();
+/// app.update();
+///
+/// app.world_mut().entity_mut(entity).insert(Regenerate::with_seed(42));
+/// app.update();
+///
+/// let generator = app.world().get::<Generator<Cartesian3D, CartesianGrid<Cartesian3D>>>(entity).unwrap();
+/// assert_eq!(generator.seed(), 42);
+/// assert!(app.world().get::<Regenerate>(entity).is_none());
+/// ```
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Regenerate {
+    /// Seed to reinitialize the generator with. If [`None`], the generator uses its next seed.
+    pub seed: Option<u64>,
+}
+
+impl Regenerate {
+    /// Regenerates with the next seed of the generator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Regenerates with the given `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        Self { seed: Some(seed) }
     }
 }
 
 /// Resource used to track generators that still need to complete.
-#[derive(Resource, Default)]
-pub struct PendingGenerations {
-    pendings: Vec<Entity>,
+///
+/// There is one resource per coordinate system `C`, grid `G` and spawner component `S`, so that plugins for different generators do not share their pending generations nor their settings.
+#[derive(Resource)]
+pub struct PendingGenerations<C: CoordinateSystem, G: Grid<C>, S: Component> {
+    pub(crate) pendings: Vec<Entity>,
+    pub(crate) states: HashMap<Entity, PendingState>,
+    max_frame_retries: Option<u32>,
+    /// Only used in [`SpawnMode::Incremental`]
+    steps_per_frame: Option<u32>,
+    typestate: PhantomData<(C, G, S)>,
+}
+
+impl<C: CoordinateSystem, G: Grid<C>, S: Component> Default for PendingGenerations<C, G, S> {
+    fn default() -> Self {
+        Self {
+            pendings: Vec::new(),
+            states: HashMap::new(),
+            max_frame_retries: None,
+            steps_per_frame: None,
+            typestate: PhantomData,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub(crate) struct PendingState {
+    /// Tries over all the frames
+    pub(crate) try_count: u32,
+    /// Frames which ended with a failed generation
+    failed_frames: u32,
+    /// Only used in [`SpawnMode::Incremental`]: the generation failed during the previous frame and should be reinitialized
+    reinitialize: bool,
 }
 
-/// Registers entities that just gained a [`Generator`] component.
-pub fn register_new_generations<C: CartesianCoordinates>(
-    mut pending_generations: ResMut<PendingGenerations>,
-    mut new_generations: Query<Entity, Added<Generator<C, CartesianGrid<C>>>>,
+impl<C: CoordinateSystem, G: Grid<C>, S: Component> PendingGenerations<C, G, S> {
+    pub(crate) fn new(max_frame_retries: Option<u32>, steps_per_frame: Option<u32>) -> Self {
+        Self {
+            max_frame_retries,
+            steps_per_frame,
+            ..Default::default()
+        }
+    }
+
+    fn register(&mut self, entity: Entity, started: &mut MessageWriter<GenerationStarted>) {
+        if !self.pendings.contains(&entity) {
+            self.pendings.push(entity);
+        }
+        self.states.insert(entity, PendingState::default());
+        started.write(GenerationStarted { entity });
+    }
+
+    /// Records a failed frame. Returns `true` if the generation should be retried on the next frame.
+    pub(crate) fn fail(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        error: GeneratorError,
+        failed: &mut MessageWriter<GenerationFailed>,
+    ) -> bool {
+        let state = self.states.entry(entity).or_default();
+        state.failed_frames += 1;
+        let will_retry = self
+            .max_frame_retries
+            .is_none_or(|max_frame_retries| state.failed_frames <= max_frame_retries);
+        failed.write(GenerationFailed {
+            entity,
+            error,
+            will_retry,
+        });
+        if !will_retry {
+            self.states.remove(&entity);
+            commands.entity(entity).insert(FailedGeneration { error });
+        }
+        will_retry
+    }
+
+    pub(crate) fn succeed(
+        &mut self,
+        entity: Entity,
+        seed: u64,
+        try_count: u32,
+        succeeded: &mut MessageWriter<GenerationSucceeded>,
+    ) {
+        self.states.remove(&entity);
+        succeeded.write(GenerationSucceeded {
+            entity,
+            seed,
+            try_count,
+        });
+    }
+    /// Attempts to generate a grid for every pending generator of `generations`. On success, the previously spawned children of the generator are despawned and `spawn` is called with the generated grid.
+    ///
+    /// Failed generations stay pending until they exceed the maximum number of frame retries.
+    pub(crate) fn generate_and_spawn_pendings<D: QueryData>(
+        &mut self,
+        commands: &mut Commands,
+        succeeded: &mut MessageWriter<GenerationSucceeded>,
+        failed: &mut MessageWriter<GenerationFailed>,
+        generations: &mut Query<(&mut Generator<C, G>, D)>,
+        mut spawn: impl FnMut(
+            &mut Commands,
+            Entity,
+            &Generator<C, G>,
+            D::Item<'_, '_>,
+            &GridData<C, ModelInstance, G>,
+        ),
+    ) where
+        G: Send + Sync + 'static,
+    {
+        let mut still_pending = Vec::new();
+
+        let pendings = std::mem::take(&mut self.pendings);
+        for entity in pendings {
+            let Ok((mut generation, data)) = generations.get_mut(entity) else {
+                self.states.remove(&entity);
+                continue;
+            };
+
+            match generation.generate_grid() {
+                Ok((info, grid_data)) => {
+                    // Remove previously spawned nodes before inserting the new set.
+                    commands.entity(entity).despawn_children();
+                    spawn(commands, entity, &generation, data, &grid_data);
+
+                    let try_count = self.states.get(&entity).map_or(0, |state| state.try_count)
+                        + info.try_count;
+                    self.succeed(entity, generation.seed(), try_count, succeeded);
+                }
+                Err(error) => {
+                    if let Some(state) = self.states.get_mut(&entity) {
+                        state.try_count += generation.max_retry_count() + 1;
+                    }
+                    // Generation failed for now, retry on the next frame.
+                    if self.fail(commands, entity, error, failed) {
+                        still_pending.push(entity);
+                    }
+                }
+            }
+        }
+
+        self.pendings = still_pending;
+    }
+}
+
+/// Registers entities with a spawner component `S` that just gained a [`Generator`] component.
+#[allow(clippy::type_complexity)]
+pub fn register_new_generations<
+    C: CoordinateSystem,
+    G: Grid<C> + Send + Sync + 'static,
+    S: Component,
+>(
+    mut pending_generations: ResMut<PendingGenerations<C, G, S>>,
+    mut started: MessageWriter<GenerationStarted>,
+    new_generations: Query<Entity, (Added<Generator<C, G>>, With<S>)>,
 ) {
-    pending_generations
-        .pendings
-        .extend(new_generations.iter_mut());
+    for entity in new_generations.iter() {
+        pending_generations.register(entity, &mut started);
+    }
+}
+
+/// Reinitializes the generators with a spawner component `S` and a [`Regenerate`] component, and queues them again.
+#[allow(clippy::type_complexity)]
+pub fn regenerate_generations<
+    C: CoordinateSystem,
+    G: Grid<C> + Send + Sync + 'static,
+    S: Component,
+>(
+    mut commands: Commands,
+    mut pending_generations: ResMut<PendingGenerations<C, G, S>>,
+    mut started: MessageWriter<GenerationStarted>,
+    mut regenerations: Query<(Entity, &mut Generator<C, G>, &Regenerate), With<S>>,
+) {
+    for (entity, mut generation, regenerate) in regenerations.iter_mut() {
+        match regenerate.seed {
+            Some(seed) => generation.reinitialize_with_seed(seed),
+            None => generation.reinitialize(),
+        };
+        commands
+            .entity(entity)
+            .remove::<(Regenerate, FailedGeneration)>();
+        pending_generations.register(entity, &mut started);
+    }
 }
 
 /// Attempts to generate a grid for every pending generator and spawns nodes on success.
-pub fn generate_and_spawn<C: CartesianCoordinates, A: BundleInserter>(
+///
+/// Nodes are placed with the layout `L` of the generator entity, or the default one if it has none.
+#[allow(clippy::type_complexity)]
+pub fn generate_and_spawn<
+    C: CoordinateSystem,
+    A: BundleInserter,
+    G: Grid<C> + Send + Sync + 'static,
+    L: NodeLayout<C, G>,
+>(
+    mut commands: Commands,
+    mut pending_generations: ResMut<PendingGenerations<C, G, NodesSpawner<A>>>,
+    mut succeeded: MessageWriter<GenerationSucceeded>,
+    mut failed: MessageWriter<GenerationFailed>,
+    mut generations: Query<(&mut Generator<C, G>, (&NodesSpawner<A>, Option<&L>))>,
+) {
+    let default_layout = L::default();
+    pending_generations.generate_and_spawn_pendings(
+        &mut commands,
+        &mut succeeded,
+        &mut failed,
+        &mut generations,
+        |commands, entity, generation, (spawner, layout), grid_data| {
+            let layout = layout.unwrap_or(&default_layout);
+            for (node_index, model_instance) in grid_data.iter().enumerate() {
+                spawn_node(
+                    commands,
+                    entity,
+                    generation.grid(),
+                    layout,
+                    spawner,
+                    model_instance,
+                    node_index,
+                );
+            }
+        },
+    );
+}
+
+/// Registers entities that just gained a [`Generator`] component, attaches a [`QueuedObserver`] to them, despawns their previous nodes and spawns their nodes which are already generated.
+#[allow(clippy::type_complexity)]
+pub fn register_new_incremental_generations<
+    C: CoordinateSystem,
+    A: BundleInserter,
+    G: Grid<C> + Send + Sync + 'static,
+    L: NodeLayout<C, G>,
+>(
     mut commands: Commands,
-    mut pending_generations: ResMut<PendingGenerations>,
-    mut generations: Query<(&mut Generator<C, CartesianGrid<C>>, &NodesSpawner<A>)>,
-    children: Query<&Children>,
+    mut pending_generations: ResMut<PendingGenerations<C, G, NodesSpawner<A>>>,
+    mut started: MessageWriter<GenerationStarted>,
+    mut new_generations: Query<
+        (Entity, &mut Generator<C, G>, &NodesSpawner<A>, Option<&L>),
+        Added<Generator<C, G>>,
+    >,
+) {
+    let default_layout = L::default();
+    for (entity, mut generation, spawner, layout) in new_generations.iter_mut() {
+        let layout = layout.unwrap_or(&default_layout);
+        let observer = QueuedObserver::new(&mut generation);
+        // A generator inserted again on the entity replaces the nodes of the previous one
+        commands.entity(entity).insert(observer).despawn_children();
+
+        // Nodes generated when the generator was built were not observed
+        for node_index in 0..generation.grid().total_size() {
+            if let [model_instance] = generation.get_models_on(node_index).as_slice() {
+                spawn_node(
+                    &mut commands,
+                    entity,
+                    generation.grid(),
+                    layout,
+                    spawner,
+                    model_instance,
+                    node_index,
+                );
+            }
+        }
+        pending_generations.register(entity, &mut started);
+    }
+}
+
+/// Advances every pending generator by at most [`SpawnMode::Incremental::steps_per_frame`] steps, reinitializing the generators which failed during the previous frame.
+pub fn generate_incrementally<
+    C: CoordinateSystem,
+    A: BundleInserter,
+    G: Grid<C> + Send + Sync + 'static,
+>(
+    mut commands: Commands,
+    mut pending_generations: ResMut<PendingGenerations<C, G, NodesSpawner<A>>>,
+    mut succeeded: MessageWriter<GenerationSucceeded>,
+    mut failed: MessageWriter<GenerationFailed>,
+    mut generations: Query<&mut Generator<C, G>>,
 ) {
     let mut still_pending = Vec::new();
 
-    for entity in pending_generations.pendings.drain(..) {
-        let Ok((mut generation, spawner)) = generations.get_mut(entity) else {
+    let pendings = std::mem::take(&mut pending_generations.pendings);
+    for entity in pendings {
+        let Ok(mut generation) = generations.get_mut(entity) else {
+            pending_generations.states.remove(&entity);
             continue;
         };
+        let state = pending_generations.states.entry(entity).or_default();
+        if state.reinitialize {
+            state.reinitialize = false;
+            state.try_count += 1;
+            generation.reinitialize();
+        } else if state.try_count == 0 {
+            state.try_count = 1;
+        }
 
-        match generation.generate_grid() {
-            Ok((_info, grid_data)) => {
-                // Remove previously spawned nodes before inserting the new set.
-                if let Ok(existing_children) = children.get(entity) {
-                    for &child in existing_children.iter() {
-                        commands.entity(child).despawn();
-                    }
-                }
+        let mut steps_left = pending_generations.steps_per_frame;
+        let mut result = Ok(GenerationStatus::Ongoing);
+        while steps_left != Some(0) {
+            result = generation.select_and_propagate();
+            if !matches!(result, Ok(GenerationStatus::Ongoing)) {
+                break;
+            }
+            steps_left = steps_left.map(|steps| steps - 1);
+        }
 
-                for (node_index, model_instance) in grid_data.iter().enumerate() {
-                    spawn_node(
-                        &mut commands,
-                        entity,
-                        &generation.grid(),
-                        spawner,
-                        model_instance,
-                        node_index,
-                    );
-                }
+        match result {
+            Ok(GenerationStatus::Ongoing) => still_pending.push(entity),
+            Ok(GenerationStatus::Done) => {
+                let try_count = pending_generations.states[&entity].try_count;
+                pending_generations.succeed(entity, generation.seed(), try_count, &mut succeeded);
             }
-            Err(GeneratorError { .. }) => {
+            Err(error) => {
                 // Generation failed for now, retry on the next frame.
-                still_pending.push(entity);
+                if pending_generations.fail(&mut commands, entity, error, &mut failed) {
+                    if let Some(state) = pending_generations.states.get_mut(&entity) {
+                        state.reinitialize = true;
+                    }
+                    still_pending.push(entity);
+                }
             }
         }
     }
 
     pending_generations.pendings = still_pending;
 }
+
+/// Spawns the generated nodes from the updates of each generator's [`QueuedObserver`], and despawns all the nodes when a generator reinitializes.
+#[allow(clippy::type_complexity)]
+pub fn spawn_generated_nodes<
+    C: CoordinateSystem,
+    A: BundleInserter,
+    G: Grid<C> + Send + Sync + 'static,
+    L: NodeLayout<C, G>,
+>(
+    mut commands: Commands,
+    mut generations: Query<(
+        Entity,
+        &mut QueuedObserver,
+        &Generator<C, G>,
+        &NodesSpawner<A>,
+        Option<&L>,
+    )>,
+) {
+    let default_layout = L::default();
+    for (entity, mut observer, generation, spawner, layout) in generations.iter_mut() {
+        let layout = layout.unwrap_or(&default_layout);
+        for update in observer.dequeue_all() {
+            match update {
+                GenerationUpdate::Generated(grid_node) => spawn_node(
+                    &mut commands,
+                    entity,
+                    generation.grid(),
+                    layout,
+                    spawner,
+                    &grid_node.model_instance,
+                    grid_node.node_index,
+                ),
+                // Despawns are queued in order with the spawns, so this also clears the nodes spawned earlier in the frame.
+                GenerationUpdate::Reinitializing(_) => {
+                    commands.entity(entity).despawn_children();
+                }
+                _ => (),
+            }
+        }
+    }
+}
@@ -2,16 +2,27 @@ use std::sync::Arc;
 
 use bevy::{
     ecs::{component::Component, system::Commands},
-    math::Vec3,
+    math::{Quat, Vec3},
     prelude::Entity,
+    transform::components::Transform,
 };
 use procedural_tilemaps_core::{
-    generator::model::ModelInstance,
-    grid::cartesian::{coordinates::CartesianCoordinates, grid::CartesianGrid},
+    generator::{
+        model::{ModelFlip, ModelInstance},
+        GeneratedNode,
+    },
+    grid::{
+        coordinate_system::CoordinateSystem,
+        direction::{Direction, DirectionTrait},
+        grid::Grid,
+    },
     NodeIndex,
 };
 
-use crate::assets::{BundleInserter, ModelsAssets};
+use crate::{
+    assets::{BundleInserter, ModelsAssets, NodeSpawnContext},
+    layout::NodeLayout,
+};
 
 /// Stores the information needed to spawn assets for generated nodes.
 #[derive(Component, Clone, Debug)]
@@ -24,16 +35,22 @@ pub struct NodesSpawner<A: BundleInserter> {
     pub spawn_scale: Vec3,
     /// Whether to offset the z coordinate based on the y position (useful for 2D layering).
     pub z_offset_from_y: bool,
+    /// World height, in nodes, over which the z offset of `z_offset_from_y` spans. If [`None`], the y size of each grid is used.
+    pub z_offset_world_height: Option<u32>,
+    /// Rotation axis of the rules of the generated models, see [`procedural_tilemaps_core::generator::rules::Rules::rotation_axis`]. Reflected models are mirrored along the world axes of its rotation plane.
+    pub rotation_axis: Direction,
 }
 
 impl<A: BundleInserter> NodesSpawner<A> {
-    /// Creates a new [`NodesSpawner`]. The `z_offset_from_y` flag defaults to `false`.
+    /// Creates a new [`NodesSpawner`]. The `z_offset_from_y` flag defaults to `false` and the rotation axis to [`Direction::ZForward`], the rotation axis of 2d rules.
     pub fn new(models_assets: ModelsAssets<A>, node_size: Vec3, spawn_scale: Vec3) -> Self {
         Self {
             assets: Arc::new(models_assets),
             node_size,
             spawn_scale,
             z_offset_from_y: false,
+            z_offset_world_height: None,
+            rotation_axis: Direction::ZForward,
         }
     }
 
@@ -42,46 +59,119 @@ impl<A: BundleInserter> NodesSpawner<A> {
         self.z_offset_from_y = z_offset_from_y;
         self
     }
+
+    /// Sets the rotation axis of the rules of the generated models, which should be [`procedural_tilemaps_core::generator::rules::Rules::rotation_axis`]. Reflected models are mirrored along the world axes of the plane orthogonal to `rotation_axis`.
+    ///
+    /// ### Example
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_procedural_tilemaps::prelude::*;
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let a = sockets.create();
+    /// sockets.add_connection(a, vec![a]);
+    /// let mut models = ModelCollection::<Cartesian3D>::new();
+    /// models.create(SocketsCartesian3D::Mono(a));
+    /// // 3d rules rotate their models around Y by default
+    /// let rules = RulesBuilder::new_cartesian_3d(models, sockets).build().unwrap();
+    ///
+    /// let spawner = NodesSpawner::new(ModelsAssets::<Handle<Image>>::new(), Vec3::ONE, Vec3::ONE)
+    ///     .with_rotation_axis(rules.rotation_axis());
+    /// // A model reflected by `ModelFlip::X` is mirrored along Z, then `ModelFlip::Y` mirrors X
+    /// assert_eq!(spawner.flipped_scale(Vec3::ONE, ModelFlip::X), Vec3::new(1., 1., -1.));
+    /// assert_eq!(spawner.flipped_scale(Vec3::ONE, ModelFlip::Y), Vec3::new(-1., 1., 1.));
+    /// ```
+    pub fn with_rotation_axis(mut self, rotation_axis: Direction) -> Self {
+        self.rotation_axis = rotation_axis;
+        self
+    }
+
+    /// Returns `scale` with the world axes mirrored by `flip` negated, see [`NodesSpawner::rotation_axis`].
+    pub fn flipped_scale(&self, mut scale: Vec3, flip: ModelFlip) -> Vec3 {
+        let basis = self.rotation_axis.rotation_basis();
+        if flip.flips_x() {
+            scale[world_axis(basis[0])] *= -1.;
+        }
+        if flip.flips_y() {
+            scale[world_axis(basis[1])] *= -1.;
+        }
+        scale
+    }
+
+    /// Sets the world height, in nodes, over which the z offset based on the y coordinate spans. Nodes from world y `0` to `z_offset_world_height` are offset from `node_size.z` down to `0`, nodes outside of this range are clamped.
+    ///
+    /// Use it when spawning several grids (such as chunks) which should be sorted together.
+    pub fn with_z_offset_world_height(mut self, z_offset_world_height: u32) -> Self {
+        self.z_offset_world_height = Some(z_offset_world_height.max(1));
+        self
+    }
+}
+
+/// Index of the world axis along `direction`
+fn world_axis(direction: Direction) -> usize {
+    match direction {
+        Direction::XForward | Direction::XBackward => 0,
+        Direction::YForward | Direction::YBackward => 1,
+        Direction::ZForward | Direction::ZBackward => 2,
+    }
 }
 
 /// Spawns the assets for a generated node as children of the generator entity.
-pub fn spawn_node<C: CartesianCoordinates, A: BundleInserter>(
+///
+/// Nodes are placed by the `layout` (see [`NodeLayout`]). The offsets of the assets are relative to the node transform given by the layout, and their grid offsets are mapped by [`NodeLayout::grid_offset_translation`].
+///
+/// Variants with their own assets spawn them without rotation nor reflection, see [`ModelsAssets::assets_for`].
+///
+/// The 60° rotation of hexagonal models ([`ModelInstance::hex_rotation`]) is applied around the Z axis to the transform inserted by the [`BundleInserter`].
+pub fn spawn_node<C: CoordinateSystem, A: BundleInserter, G: Grid<C>, L: NodeLayout<C, G>>(
     commands: &mut Commands,
     parent: Entity,
-    grid: &CartesianGrid<C>,
+    grid: &G,
+    layout: &L,
     spawner: &NodesSpawner<A>,
     instance: &ModelInstance,
     node_index: NodeIndex,
 ) {
-    let Some(node_assets) = spawner.assets.get(&instance.model_index) else {
+    let Some((node_assets, rotation, flip)) = spawner.assets.assets_for(instance) else {
         return;
     };
 
-    let position = grid.pos_from_index(node_index);
+    let node_transform = layout.node_transform(grid, node_index, spawner);
+    // Reflections are applied through the scale, before the rotation
+    let scale = spawner.flipped_scale(spawner.spawn_scale * node_transform.scale, flip);
     for asset in node_assets.iter() {
         // Center the entity within the node while applying optional offsets.
-        let mut translation = Vec3::new(
-            asset.world_offset.x
-                + spawner.node_size.x * (position.x as f32 + asset.grid_offset.dx as f32 + 0.5),
-            asset.world_offset.y
-                + spawner.node_size.y * (position.y as f32 + asset.grid_offset.dy as f32 + 0.5),
-            asset.world_offset.z
-                + spawner.node_size.z * (position.z as f32 + asset.grid_offset.dz as f32 + 0.5),
-        );
-
-        if spawner.z_offset_from_y {
-            translation.z += spawner.node_size.z * (1. - position.y as f32 / grid.size_y() as f32);
-        }
+        let offset = asset.world_offset
+            + layout.grid_offset_translation(grid, node_index, spawner, &asset.grid_offset);
+        let translation = node_transform.transform_point(offset);
 
         let entity = commands.spawn_empty().id();
         let entity_commands = &mut commands.entity(entity);
-        asset.assets_bundle.insert_bundle(
+        asset
+            .assets_bundle
+            .insert_bundle(entity_commands, translation, scale, rotation);
+        // Hexagonal models are rotated by steps of 60° around Z, after the rotation given to the bundle inserter
+        let node_rotation =
+            node_transform.rotation * Quat::from_rotation_z(instance.hex_rotation.rad());
+        if node_rotation != Quat::IDENTITY {
+            entity_commands
+                .entry::<Transform>()
+                .and_modify(move |mut transform| {
+                    transform.rotation = node_rotation * transform.rotation
+                });
+        }
+        asset.spawn_hook.run(
             entity_commands,
-            translation,
-            spawner.spawn_scale,
-            instance.rotation,
+            &NodeSpawnContext {
+                generator: parent,
+                node: GeneratedNode {
+                    node_index,
+                    model_instance: *instance,
+                },
+                world_position: layout.world_position(grid, node_index),
+                translation,
+            },
         );
-        (asset.spawn_commands)(entity_commands);
 
         commands.entity(parent).add_child(entity);
     }
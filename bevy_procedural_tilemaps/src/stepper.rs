@@ -0,0 +1,421 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        message::{Message, MessageReader, MessageWriter},
+        schedule::IntoScheduleConfigs,
+        system::{Commands, Query, Res},
+    },
+    time::Time,
+};
+use procedural_tilemaps_core::{
+    generator::{GeneratedNode, GenerationStatus, Generator},
+    grid::{cartesian::grid::CartesianGrid, coordinate_system::CoordinateSystem, Grid},
+    GeneratorError,
+};
+
+use crate::{
+    assets::BundleInserter,
+    layout::{CartesianLayout, NodeLayout},
+    simple_plugin::{FailedGeneration, GenerationFailed},
+    spawner::{spawn_node, NodesSpawner},
+};
+
+/// Plugin that advances every generator with a [`GenerationStepper`] one step at a time, spawning the generated nodes as they are generated.
+///
+/// Nodes are placed with the layout `L` of the generator entity, or the default one if it has none.
+///
+/// Steppers are controlled by sending [`StepperCommand`] messages, which can be bound to any input.
+///
+/// Each failed generation is reported with a [`GenerationFailed`] message, and restarted on the next step, in the next frame at the earliest. A stepper which fails more than its maximum number of retries (see [`GenerationStepper::with_max_retries`]) stops and marks its generator with a [`FailedGeneration`] component.
+///
+/// **Note**: the [`crate::simple_plugin::ProcGenSimplePlugin`] generates every [`Generator`] at once, it should not run on the same generators.
+///
+/// ### Example
+///
+/// The stepper does not need a window:
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_procedural_tilemaps::prelude::*;
+///
+/// let mut sockets = SocketCollection::new();
+/// let a = sockets.create();
+/// sockets.add_connection(a, vec![a]);
+/// let mut models = ModelCollection::<Cartesian3D>::new();
+/// models.create(SocketsCartesian3D::Mono(a));
+/// models.create(SocketsCartesian3D::Mono(a));
+/// let rules = RulesBuilder::new_cartesian_3d(models, sockets).build().unwrap();
+/// let generator = GeneratorBuilder::new()
+///     .with_rules(rules)
+///     .with_grid(CartesianGrid::new_cartesian_3d(4, 4, 1, false, false, false))
+///     .build()
+///     .unwrap();
+/// let spawner = NodesSpawner::<Handle<Image>>::new(ModelsAssets::new(), Vec3::ONE, Vec3::ONE);
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     ProcGenStepperPlugin::<Cartesian3D, Handle<Image>>::default(),
+/// ));
+/// let entity = app
+///     .world_mut()
+///     .spawn((generator, spawner, GenerationStepper::new(StepperMode::SingleStep)))
+///     .id();
+///
+/// // Nothing happens until a step is requested
+/// app.update();
+/// assert_eq!(app.world().get::<GenerationStepper>(entity).unwrap().steps(), 0);
+///
+/// app.world_mut().write_message(StepperCommand::Step);
+/// app.update();
+/// assert_eq!(app.world().get::<GenerationStepper>(entity).unwrap().steps(), 1);
+///
+/// app.world_mut().write_message(StepperCommand::SetMode(StepperMode::RunToEnd));
+/// app.update();
+/// let stepper = app.world().get::<GenerationStepper>(entity).unwrap();
+/// assert!(stepper.is_done());
+/// ```
+pub struct ProcGenStepperPlugin<
+    C: CoordinateSystem,
+    A: BundleInserter,
+    G: Grid<C> + Send + Sync + 'static = CartesianGrid<C>,
+    L: NodeLayout<C, G> = CartesianLayout,
+> {
+    typestate: PhantomData<(C, A, G, L)>,
+}
+
+impl<
+        C: CoordinateSystem,
+        A: BundleInserter,
+        G: Grid<C> + Send + Sync + 'static,
+        L: NodeLayout<C, G>,
+    > Default for ProcGenStepperPlugin<C, A, G, L>
+{
+    fn default() -> Self {
+        Self {
+            typestate: PhantomData,
+        }
+    }
+}
+
+impl<
+        C: CoordinateSystem,
+        A: BundleInserter,
+        G: Grid<C> + Send + Sync + 'static,
+        L: NodeLayout<C, G>,
+    > Plugin for ProcGenStepperPlugin<C, A, G, L>
+{
+    fn build(&self, app: &mut App) {
+        app.add_message::<StepperCommand>();
+        app.add_message::<GenerationFailed>();
+        app.add_systems(
+            Update,
+            (apply_stepper_commands, step_generations::<C, A, G, L>).chain(),
+        );
+    }
+}
+
+/// How a [`GenerationStepper`] advances its generator
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum StepperMode {
+    /// The generator does not advance. [`StepperCommand::Step`] are ignored.
+    #[default]
+    Paused,
+    /// The generator advances by one step for each [`StepperCommand::Step`]
+    SingleStep,
+    /// The generator advances by a fixed number of steps per second
+    StepsPerSecond(f32),
+    /// The generator advances until the generation is done, in a single frame
+    RunToEnd,
+}
+
+/// Messages controlling the [`GenerationStepper`]s
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub enum StepperCommand {
+    /// Changes the mode of all the steppers
+    SetMode(StepperMode),
+    /// Requests one step from all the steppers in [`StepperMode::SingleStep`]
+    Step,
+    /// Reinitializes the generators of all the steppers with their next seed. Steppers which gave up after too many failures start again.
+    Reinitialize,
+}
+
+/// Component controlling the step-by-step generation of a [`Generator`] on the same entity. See [`ProcGenStepperPlugin`].
+///
+/// A step is one call to [`Generator::select_and_propagate_collected`], or to [`Generator::reinitialize_collected`] after a contradiction.
+#[derive(Component, Clone, Debug, Default)]
+pub struct GenerationStepper {
+    /// Current mode of the stepper
+    pub mode: StepperMode,
+    pending_steps: u32,
+    pending_reinitialize: bool,
+    steps_accumulator: f32,
+    steps: u32,
+    last_result: Option<Result<GenerationStatus, GeneratorError>>,
+    initialized: bool,
+    max_retries: Option<u32>,
+    failures: u32,
+}
+
+impl GenerationStepper {
+    /// Creates a new [`GenerationStepper`] in the given `mode`
+    pub fn new(mode: StepperMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    /// Sets how many failed generations are restarted before the stepper gives up. Unlimited by default.
+    ///
+    /// ### Example
+    ///
+    /// Models which must alternate on a looping row of odd length always fail:
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_procedural_tilemaps::prelude::*;
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let (p_pos, p_neg, q_pos, q_neg, other) =
+    ///     (sockets.create(), sockets.create(), sockets.create(), sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(p_pos, vec![q_neg]), (q_pos, vec![p_neg]), (other, vec![other])]);
+    /// let mut models = ModelCollection::<Cartesian3D>::new();
+    /// for (x_pos, x_neg) in [(p_pos, p_neg), (q_pos, q_neg)] {
+    ///     models.create(SocketsCartesian3D::Simple {
+    ///         x_pos,
+    ///         x_neg,
+    ///         z_pos: other,
+    ///         z_neg: other,
+    ///         y_pos: other,
+    ///         y_neg: other,
+    ///     });
+    /// }
+    /// let rules = RulesBuilder::new_cartesian_3d(models, sockets).build().unwrap();
+    /// let generator = GeneratorBuilder::new()
+    ///     .with_rules(rules)
+    ///     .with_grid(CartesianGrid::new_cartesian_3d(3, 1, 1, true, false, false))
+    ///     .build()
+    ///     .unwrap();
+    /// let spawner = NodesSpawner::<Handle<Image>>::new(ModelsAssets::new(), Vec3::ONE, Vec3::ONE);
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins((
+    ///     MinimalPlugins,
+    ///     ProcGenStepperPlugin::<Cartesian3D, Handle<Image>>::default(),
+    /// ));
+    /// let stepper = GenerationStepper::new(StepperMode::RunToEnd).with_max_retries(2);
+    /// let entity = app.world_mut().spawn((generator, spawner, stepper)).id();
+    ///
+    /// // Each frame ends at the first failure, the third one is given up
+    /// for _ in 0..5 {
+    ///     app.update();
+    /// }
+    /// let stepper = app.world().get::<GenerationStepper>(entity).unwrap();
+    /// assert!(stepper.has_given_up());
+    /// assert!(app.world().get::<FailedGeneration>(entity).is_some());
+    /// ```
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Returns the number of steps executed since the stepper was created
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// Returns the result of the last step, or [`None`] if no step was executed yet
+    pub fn last_result(&self) -> Option<Result<GenerationStatus, GeneratorError>> {
+        self.last_result
+    }
+
+    /// Returns `true` if the generation is done
+    pub fn is_done(&self) -> bool {
+        matches!(self.last_result, Some(Ok(GenerationStatus::Done)))
+    }
+
+    /// Returns `true` if the stepper gave up after failing more than its maximum number of retries
+    pub fn has_given_up(&self) -> bool {
+        self.max_retries
+            .is_some_and(|max_retries| self.failures > max_retries)
+    }
+
+    /// Number of steps to execute this frame
+    fn steps_to_run(&mut self, delta_secs: f32) -> Option<u32> {
+        match self.mode {
+            StepperMode::Paused => Some(0),
+            StepperMode::SingleStep => Some(std::mem::take(&mut self.pending_steps)),
+            StepperMode::StepsPerSecond(steps_per_second) => {
+                self.steps_accumulator += delta_secs * steps_per_second.max(0.);
+                let steps = self.steps_accumulator.floor();
+                self.steps_accumulator -= steps;
+                Some(steps as u32)
+            }
+            StepperMode::RunToEnd => None,
+        }
+    }
+}
+
+/// Applies the received [`StepperCommand`]s to all the [`GenerationStepper`]s
+pub fn apply_stepper_commands(
+    mut stepper_commands: MessageReader<StepperCommand>,
+    mut steppers: Query<&mut GenerationStepper>,
+) {
+    for command in stepper_commands.read() {
+        for mut stepper in steppers.iter_mut() {
+            match *command {
+                StepperCommand::SetMode(mode) => {
+                    stepper.mode = mode;
+                    stepper.pending_steps = 0;
+                    stepper.steps_accumulator = 0.;
+                }
+                StepperCommand::Step => {
+                    if stepper.mode == StepperMode::SingleStep {
+                        stepper.pending_steps += 1;
+                    }
+                }
+                StepperCommand::Reinitialize => stepper.pending_reinitialize = true,
+            }
+        }
+    }
+}
+
+/// Advances the generators according to their [`GenerationStepper`], spawning the generated nodes and despawning all the nodes when a generator reinitializes.
+///
+/// Despawns are queued in order with the spawns, so a reinitialization also clears the nodes spawned earlier in the same frame.
+#[allow(clippy::type_complexity)]
+pub fn step_generations<
+    C: CoordinateSystem,
+    A: BundleInserter,
+    G: Grid<C> + Send + Sync + 'static,
+    L: NodeLayout<C, G>,
+>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut failed: MessageWriter<GenerationFailed>,
+    mut generations: Query<(
+        Entity,
+        &mut Generator<C, G>,
+        &mut GenerationStepper,
+        &NodesSpawner<A>,
+        Option<&L>,
+    )>,
+) {
+    let default_layout = L::default();
+    for (entity, mut generator, mut stepper, spawner, layout) in generations.iter_mut() {
+        let layout = layout.unwrap_or(&default_layout);
+        if !stepper.initialized {
+            // Spawn the nodes which were already generated when the generator was built
+            stepper.initialized = true;
+            commands.entity(entity).despawn_children();
+            for node_index in 0..generator.grid().total_size() {
+                if let [model_instance] = generator.get_models_on(node_index).as_slice() {
+                    spawn_node(
+                        &mut commands,
+                        entity,
+                        generator.grid(),
+                        layout,
+                        spawner,
+                        model_instance,
+                        node_index,
+                    );
+                }
+            }
+        }
+
+        if stepper.pending_reinitialize {
+            stepper.pending_reinitialize = false;
+            stepper.failures = 0;
+            commands.entity(entity).remove::<FailedGeneration>();
+            let (status, generated_nodes) = generator.reinitialize_collected();
+            commands.entity(entity).despawn_children();
+            spawn_nodes(
+                &mut commands,
+                entity,
+                &generator,
+                layout,
+                spawner,
+                &generated_nodes,
+            );
+            stepper.last_result = Some(Ok(status));
+        }
+
+        let mut steps_left = stepper.steps_to_run(time.delta_secs());
+        while steps_left != Some(0) {
+            if stepper.is_done() || stepper.has_given_up() {
+                break;
+            }
+            match stepper.last_result {
+                Some(Err(_)) => {
+                    // The generator failed on the previous step: restart the generation.
+                    let (status, generated_nodes) = generator.reinitialize_collected();
+                    commands.entity(entity).despawn_children();
+                    spawn_nodes(
+                        &mut commands,
+                        entity,
+                        &generator,
+                        layout,
+                        spawner,
+                        &generated_nodes,
+                    );
+                    stepper.last_result = Some(Ok(status));
+                }
+                _ => match generator.select_and_propagate_collected() {
+                    Ok((status, generated_nodes)) => {
+                        spawn_nodes(
+                            &mut commands,
+                            entity,
+                            &generator,
+                            layout,
+                            spawner,
+                            &generated_nodes,
+                        );
+                        stepper.last_result = Some(Ok(status));
+                    }
+                    Err(error) => {
+                        stepper.last_result = Some(Err(error));
+                        stepper.failures += 1;
+                        let will_retry = !stepper.has_given_up();
+                        failed.write(GenerationFailed {
+                            entity,
+                            error,
+                            will_retry,
+                        });
+                        if !will_retry {
+                            commands.entity(entity).insert(FailedGeneration { error });
+                        }
+                        // Keep the failed generation visible until the next frame
+                        stepper.steps += 1;
+                        break;
+                    }
+                },
+            }
+            stepper.steps += 1;
+            steps_left = steps_left.map(|steps| steps - 1);
+        }
+    }
+}
+
+fn spawn_nodes<C: CoordinateSystem, A: BundleInserter, G: Grid<C>, L: NodeLayout<C, G>>(
+    commands: &mut Commands,
+    entity: Entity,
+    generator: &Generator<C, G>,
+    layout: &L,
+    spawner: &NodesSpawner<A>,
+    generated_nodes: &[GeneratedNode],
+) {
+    for node in generated_nodes {
+        spawn_node(
+            commands,
+            entity,
+            generator.grid(),
+            layout,
+            spawner,
+            &node.model_instance,
+            node.node_index,
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+
+use bevy::{
+    app::{App, Plugin, Update},
+    asset::Handle,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        message::MessageWriter,
+        schedule::IntoScheduleConfigs,
+        system::{Commands, Query, ResMut},
+    },
+    image::Image,
+    math::{UVec2, Vec3},
+    sprite_render::{AlphaMode2d, TileData, TilemapChunk, TilemapChunkTileData},
+    transform::components::Transform,
+};
+use procedural_tilemaps_core::{
+    generator::{
+        model::{ModelFlip, ModelIndex, ModelInstance, ModelRotation, ALL_MODEL_ROTATIONS},
+        Generator,
+    },
+    grid::{
+        cartesian::{coordinates::CartesianCoordinates, grid::CartesianGrid},
+        GridData,
+    },
+};
+
+use crate::{
+    assets::variant_key,
+    simple_plugin::{
+        regenerate_generations, register_new_generations, GenerationFailed, GenerationStarted,
+        GenerationSucceeded, PendingGenerations,
+    },
+};
+
+/// Plugin that runs every generator with a [`TilemapChunksSpawner`] once per frame until a valid grid is produced, then spawns the generated grid as tilemap chunks.
+///
+/// This is the counterpart of [`crate::simple_plugin::ProcGenSimplePlugin`] for chunked rendering: both plugins can be added to the same app, each one handling the generators with its own spawner.
+///
+/// Generations follow the same lifecycle as with the [`crate::simple_plugin::ProcGenSimplePlugin`]: they write the [`GenerationStarted`], [`GenerationSucceeded`] and [`GenerationFailed`] messages, are given up after [`ProcGenTilemapPlugin::with_max_frame_retries`] failed frames, and are rerolled by a [`crate::simple_plugin::Regenerate`] component.
+pub struct ProcGenTilemapPlugin<C: CartesianCoordinates> {
+    max_frame_retries: Option<u32>,
+    typestate: PhantomData<C>,
+}
+
+impl<C: CartesianCoordinates> Default for ProcGenTilemapPlugin<C> {
+    fn default() -> Self {
+        Self {
+            max_frame_retries: None,
+            typestate: PhantomData,
+        }
+    }
+}
+
+impl<C: CartesianCoordinates> ProcGenTilemapPlugin<C> {
+    /// Sets how many frames a failed generation is retried before the generator is marked with a [`crate::simple_plugin::FailedGeneration`] component. Unlimited by default.
+    ///
+    /// ### Example
+    ///
+    /// Models which must alternate on a looping row of odd length always fail:
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_procedural_tilemaps::prelude::*;
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let (p_pos, p_neg, q_pos, q_neg, other) =
+    ///     (sockets.create(), sockets.create(), sockets.create(), sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(p_pos, vec![q_neg]), (q_pos, vec![p_neg]), (other, vec![other])]);
+    /// let mut models = ModelCollection::<Cartesian2D>::new();
+    /// for (x_pos, x_neg) in [(p_pos, p_neg), (q_pos, q_neg)] {
+    ///     models.create(SocketsCartesian2D::Simple { x_pos, x_neg, y_pos: other, y_neg: other });
+    /// }
+    /// let rules = RulesBuilder::new_cartesian_2d(models, sockets).build().unwrap();
+    /// let generator = GeneratorBuilder::new()
+    ///     .with_rules(rules)
+    ///     .with_grid(CartesianGrid::new_cartesian_2d(3, 1, true, false))
+    ///     .with_max_retry_count(0)
+    ///     .build()
+    ///     .unwrap();
+    /// let spawner = TilemapChunksSpawner::new(Handle::default(), TilesetIndices::new(), Vec3::ONE);
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins((
+    ///     MinimalPlugins,
+    ///     ProcGenTilemapPlugin::<Cartesian2D>::default().with_max_frame_retries(1),
+    /// ));
+    /// let entity = app.world_mut().spawn((generator, spawner)).id();
+    ///
+    /// // The first failed frame is retried, the second one is given up
+    /// app.update();
+    /// assert!(app.world().get::<FailedGeneration>(entity).is_none());
+    /// app.update();
+    /// assert!(app.world().get::<FailedGeneration>(entity).is_some());
+    ///
+    /// // Regenerating restarts the generation
+    /// app.world_mut().entity_mut(entity).insert(Regenerate::new());
+    /// app.update();
+    /// assert!(app.world().get::<FailedGeneration>(entity).is_none());
+    /// ```
+    pub fn with_max_frame_retries(mut self, max_frame_retries: u32) -> Self {
+        self.max_frame_retries = Some(max_frame_retries);
+        self
+    }
+}
+
+impl<C: CartesianCoordinates> Plugin for ProcGenTilemapPlugin<C> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PendingTilemapGenerations::<C>::new(
+            self.max_frame_retries,
+            None,
+        ));
+        app.add_message::<GenerationStarted>();
+        app.add_message::<GenerationSucceeded>();
+        app.add_message::<GenerationFailed>();
+        app.add_systems(
+            Update,
+            (
+                register_new_generations::<C, CartesianGrid<C>, TilemapChunksSpawner>,
+                regenerate_generations::<C, CartesianGrid<C>, TilemapChunksSpawner>,
+                generate_and_spawn_chunks::<C>,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Links model variants (a [`ModelIndex`] with a [`ModelRotation`] and a [`ModelFlip`]) to their index in a tileset.
+///
+/// Tilemap chunks cannot rotate their tiles: each rotation (or reflection) of a model that can appear in the generation needs its own tile in the tileset.
+///
+/// Variants are keyed by their canonical form (see [`ModelFlip::canonical`]), so a tile linked with [`ModelFlip::Y`] or [`ModelFlip::XY`] is used for the equivalent instances emitted by the generator.
+#[derive(Clone, Debug, Default)]
+pub struct TilesetIndices {
+    map: HashMap<(ModelIndex, ModelRotation, ModelFlip), u16>,
+}
+
+impl TilesetIndices {
+    /// Creates a new empty [`TilesetIndices`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Links the model `model_index` with the rotation `rotation` (and no reflection) to the tile `tileset_index`
+    pub fn add(&mut self, model_index: ModelIndex, rotation: ModelRotation, tileset_index: u16) {
+        self.add_variant(model_index, rotation, ModelFlip::None, tileset_index);
+    }
+
+    /// Links the model `model_index` with the rotation `rotation` and the reflection `flip` to the tile `tileset_index`
+    pub fn add_variant(
+        &mut self,
+        model_index: ModelIndex,
+        rotation: ModelRotation,
+        flip: ModelFlip,
+        tileset_index: u16,
+    ) {
+        self.map
+            .insert(variant_key(model_index, rotation, flip), tileset_index);
+    }
+
+    /// Links all the rotations of the model `model_index` (without reflection) to the same tile `tileset_index`. Useful for models which are symmetric or whose rotations are not allowed.
+    ///
+    /// Only [`ModelFlip::None`] is covered: if the model has reflections, its [`ModelFlip::X`] variants need their own tiles (see [`TilesetIndices::add_variant`]) or their nodes are left empty.
+    pub fn add_all_rotations(&mut self, model_index: ModelIndex, tileset_index: u16) {
+        for rotation in ALL_MODEL_ROTATIONS {
+            self.add(model_index, *rotation, tileset_index);
+        }
+    }
+
+    /// Returns the tile index of a model instance, if any
+    pub fn get(&self, instance: &ModelInstance) -> Option<u16> {
+        self.map
+            .get(&variant_key(
+                instance.model_index,
+                instance.rotation,
+                instance.flip,
+            ))
+            .copied()
+    }
+}
+
+/// Stores the information needed to render a generated grid with tilemap chunks, as an alternative to the one entity per tile [`crate::spawner::NodesSpawner`].
+///
+/// Each z layer of the grid is split into chunks of at most `chunk_size` nodes, each rendered as a single mesh by Bevy's [`TilemapChunk`].
+#[derive(Component, Clone, Debug)]
+pub struct TilemapChunksSpawner {
+    /// Array texture containing all the tiles
+    pub tileset: Handle<Image>,
+    /// Link a model variant to its tile in the `tileset`. Models without a tile leave their node empty.
+    pub indices: Arc<TilesetIndices>,
+    /// Size of a node in world units. The z component is the distance between two z layers.
+    pub node_size: Vec3,
+    /// Maximum size of a chunk, in nodes.
+    pub chunk_size: UVec2,
+    /// Alpha mode of the chunks
+    pub alpha_mode: AlphaMode2d,
+}
+
+impl TilemapChunksSpawner {
+    /// Creates a new [`TilemapChunksSpawner`]. The chunk size defaults to 64x64 nodes and the alpha mode to [`AlphaMode2d::Blend`].
+    pub fn new(tileset: Handle<Image>, indices: TilesetIndices, node_size: Vec3) -> Self {
+        Self {
+            tileset,
+            indices: Arc::new(indices),
+            node_size,
+            chunk_size: UVec2::splat(64),
+            alpha_mode: AlphaMode2d::Blend,
+        }
+    }
+
+    /// Sets the maximum size of a chunk, in nodes
+    pub fn with_chunk_size(mut self, chunk_size: UVec2) -> Self {
+        self.chunk_size = chunk_size.max(UVec2::ONE);
+        self
+    }
+
+    /// Sets the alpha mode of the chunks
+    pub fn with_alpha_mode(mut self, alpha_mode: AlphaMode2d) -> Self {
+        self.alpha_mode = alpha_mode;
+        self
+    }
+
+    /// Builds the chunks components for `grid_data`, without spawning them.
+    ///
+    /// Chunks are placed like the nodes of [`crate::spawner::spawn_node`], at their world position (see [`CartesianGrid::origin`]).
+    ///
+    /// ### Example
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_procedural_tilemaps::{prelude::*, proc_gen::generator::model::ModelInstance};
+    ///
+    /// let grid = CartesianGrid::new_cartesian_3d(100, 40, 2, false, false, false);
+    /// let grid_data = grid.new_grid_data(ModelInstance {
+    ///     model_index: 0,
+    ///     rotation: ModelRotation::Rot90,
+    ///     flip: ModelFlip::None,
+    ///     hex_rotation: HexRotation::Rot0,
+    /// });
+    ///
+    /// let mut indices = TilesetIndices::new();
+    /// indices.add(0, ModelRotation::Rot90, 3);
+    /// let spawner = TilemapChunksSpawner::new(Handle::default(), indices, Vec3::new(16., 16., 1.))
+    ///     .with_chunk_size(UVec2::new(64, 64));
+    ///
+    /// let chunks = spawner.build_chunks(&grid_data);
+    /// // 2 chunks per row, 1 row, 2 layers
+    /// assert_eq!(chunks.len(), 4);
+    /// let (transform, chunk, tile_data) = &chunks[1];
+    /// assert_eq!(chunk.chunk_size, UVec2::new(36, 40));
+    /// assert_eq!(transform.translation, Vec3::new(16. * 82., 16. * 20., 0.5));
+    /// assert!(tile_data.iter().all(|tile| tile.unwrap().tileset_index == 3));
+    /// ```
+    pub fn build_chunks<C: CartesianCoordinates>(
+        &self,
+        grid_data: &GridData<C, ModelInstance, CartesianGrid<C>>,
+    ) -> Vec<(Transform, TilemapChunk, TilemapChunkTileData)> {
+        let grid = grid_data.grid();
+        let origin = grid.origin();
+        let tile_display_size = UVec2::new(self.node_size.x as u32, self.node_size.y as u32);
+
+        let mut chunks = Vec::new();
+        for z in 0..grid.size_z() {
+            for chunk_y in (0..grid.size_y()).step_by(self.chunk_size.y as usize) {
+                for chunk_x in (0..grid.size_x()).step_by(self.chunk_size.x as usize) {
+                    // Chunks on the far borders of the grid may be smaller
+                    let chunk_size = UVec2::new(
+                        self.chunk_size.x.min(grid.size_x() - chunk_x),
+                        self.chunk_size.y.min(grid.size_y() - chunk_y),
+                    );
+
+                    // Tiles are stored row by row, starting from the bottom row
+                    let mut tiles = Vec::with_capacity(chunk_size.element_product() as usize);
+                    for y in chunk_y..chunk_y + chunk_size.y {
+                        for x in chunk_x..chunk_x + chunk_size.x {
+                            let instance = grid_data.get(grid.index_from_coords(x, y, z));
+                            tiles
+                                .push(self.indices.get(instance).map(TileData::from_tileset_index));
+                        }
+                    }
+
+                    // The chunk mesh is centered on its transform
+                    let translation = Vec3::new(
+                        self.node_size.x
+                            * ((origin.x + chunk_x as i32) as f32 + chunk_size.x as f32 / 2.),
+                        self.node_size.y
+                            * ((origin.y + chunk_y as i32) as f32 + chunk_size.y as f32 / 2.),
+                        self.node_size.z * ((origin.z + z as i32) as f32 + 0.5),
+                    );
+                    chunks.push((
+                        Transform::from_translation(translation),
+                        TilemapChunk {
+                            chunk_size,
+                            tile_display_size,
+                            tileset: self.tileset.clone(),
+                            alpha_mode: self.alpha_mode,
+                        },
+                        TilemapChunkTileData(tiles),
+                    ));
+                }
+            }
+        }
+        chunks
+    }
+}
+
+/// Spawns the tilemap chunks of a generated grid as children of the generator entity.
+pub fn spawn_tilemap_chunks<C: CartesianCoordinates>(
+    commands: &mut Commands,
+    parent: Entity,
+    spawner: &TilemapChunksSpawner,
+    grid_data: &GridData<C, ModelInstance, CartesianGrid<C>>,
+) {
+    for (transform, chunk, tile_data) in spawner.build_chunks(grid_data) {
+        let entity = commands.spawn((transform, chunk, tile_data)).id();
+        commands.entity(parent).add_child(entity);
+    }
+}
+
+/// Resource used to track generators with a [`TilemapChunksSpawner`] that still need to complete.
+pub type PendingTilemapGenerations<C> =
+    PendingGenerations<C, CartesianGrid<C>, TilemapChunksSpawner>;
+
+/// Attempts to generate a grid for every pending generator and spawns its tilemap chunks on success.
+pub fn generate_and_spawn_chunks<C: CartesianCoordinates>(
+    mut commands: Commands,
+    mut pending_generations: ResMut<PendingTilemapGenerations<C>>,
+    mut succeeded: MessageWriter<GenerationSucceeded>,
+    mut failed: MessageWriter<GenerationFailed>,
+    mut generations: Query<(&mut Generator<C, CartesianGrid<C>>, &TilemapChunksSpawner)>,
+) {
+    pending_generations.generate_and_spawn_pendings(
+        &mut commands,
+        &mut succeeded,
+        &mut failed,
+        &mut generations,
+        |commands, entity, _, spawner, grid_data| {
+            spawn_tilemap_chunks(commands, entity, spawner, grid_data);
+        },
+    );
+}
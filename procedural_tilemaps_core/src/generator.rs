@@ -1,5 +1,9 @@
 use core::fmt;
-use std::{collections::HashMap, collections::HashSet, sync::Arc};
+use std::{
+    collections::HashMap,
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
 
 #[cfg(feature = "bevy")]
 use bevy::ecs::component::Component;
@@ -14,9 +18,8 @@ use crate::{GeneratorError, NodeIndex, NodeSetError};
 use self::{
     builder::{GeneratorBuilder, Unset},
     internal_generator::{InternalGenerator, InternalGeneratorStatus},
-    model::{ModelIndex, ModelInstance, ModelRotation, ModelVariantIndex},
-    node_heuristic::NodeSelectionHeuristic,
-    observer::GenerationUpdate,
+    model::{ModelFlip, ModelIndex, ModelInstance, ModelRotation, ModelVariantIndex},
+    observer::{DetailedUpdates, GenerationListener, GenerationUpdate, ObserverQueue},
     rules::{ModelInfo, ModelVariantRef, Rules},
 };
 
@@ -24,12 +27,15 @@ use self::{
 pub mod builder;
 /// Defines [`crate::generator::model::Model`] and their associated type & utilities
 pub mod model;
-/// Defines the different possible [`NodeSelectionHeuristic`]
+/// Defines the different possible [`node_heuristic::NodeSelectionHeuristic`]
 pub mod node_heuristic;
 /// Defines different possible observers to view the results:execution of a [`Generator`]
 pub mod observer;
 /// Defines the [`Rules`] used by a [`Generator`]
 pub mod rules;
+/// Serializable definitions of [`Rules`], with sockets referenced by name
+#[cfg(feature = "serde")]
+pub mod rules_definition;
 /// Defines [`crate::generator::socket::Socket`] and their associated type & utilities
 pub mod socket;
 
@@ -94,6 +100,9 @@ enum NodeSetStatus {
 
 type Collector<'a> = Option<&'a mut Vec<GeneratedNode>>;
 
+/// Merged grid and changed nodes of a regenerated region
+type RegeneratedRegion<C, G> = (GridData<C, ModelInstance, G>, Vec<GeneratedNode>);
+
 /// Model synthesis/WFC generator.
 /// Use a [`GeneratorBuilder`] to get an instance of a [`Generator`].
 #[cfg_attr(feature = "bevy", derive(Component))]
@@ -113,29 +122,15 @@ impl<C: CoordinateSystem, G: Grid<C>> Generator<C, G> {
     }
 
     fn create(
-        rules: Arc<Rules<C>>,
-        grid: G,
+        internal: InternalGenerator<C, G>,
         initial_nodes: Vec<(NodeIndex, ModelVariantIndex)>,
         max_retry_count: u32,
-        node_selection_heuristic: NodeSelectionHeuristic,
-        model_selection_heuristic: ModelSelectionHeuristic,
-        rng_mode: RngMode,
-        observers: Vec<crossbeam_channel::Sender<GenerationUpdate>>,
-        border_zones: HashSet<(usize, usize)>,
         collector: &mut Collector,
     ) -> Result<Self, NodeSetError> {
         let mut generator = Self {
             max_retry_count,
             initial_nodes,
-            internal: InternalGenerator::new(
-                rules,
-                grid,
-                node_selection_heuristic,
-                model_selection_heuristic,
-                rng_mode,
-                observers,
-                border_zones,
-            ),
+            internal,
         };
         match generator
             .internal
@@ -246,7 +241,7 @@ impl<C: CoordinateSystem, G: Grid<C>> Generator<C, G> {
         model_variant_ref: M,
         memorized: bool,
     ) -> Result<GenerationStatus, NodeSetError> {
-        let node_index = node_ref.to_index(&self.internal.grid);
+        let node_index = self.node_index(&node_ref)?;
         let model_variant_index = model_variant_ref.to_index(&self.internal.rules)?;
         let status = self
             .internal
@@ -257,7 +252,31 @@ impl<C: CoordinateSystem, G: Grid<C>> Generator<C, G> {
         Ok(status)
     }
 
-    /// Same as [`Generator::set_and_propagate`] but also returns all the [`GeneratedNode`] generated by this generation operation if successful.
+    /// Same as [`Generator::set_and_propagate`] but also returns all the [`GeneratedNode`] generated by this generation operation if successful, starting with the node that was set (if it was not already generated).
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use procedural_tilemaps_core::prelude::*;
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let (land, water) = (sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(land, vec![land]), (water, vec![water])]);
+    ///
+    /// let mut models = ModelCollection::<Cartesian2D>::new();
+    /// models.create(SocketsCartesian2D::Mono(land));
+    /// models.create(SocketsCartesian2D::Mono(water));
+    ///
+    /// let rules = RulesBuilder::new_cartesian_2d(models, sockets).build().unwrap();
+    /// let grid = CartesianGrid::new_cartesian_2d(4, 4, false, false);
+    /// let mut generator = GeneratorBuilder::new().with_rules(rules).with_grid(grid).build().unwrap();
+    ///
+    /// // Setting water on one node forces water on all the others
+    /// let (status, nodes) = generator.set_and_propagate_collected(0, (1, ModelRotation::Rot0), false).unwrap();
+    /// assert_eq!(status, GenerationStatus::Done);
+    /// assert_eq!(nodes.len(), 16);
+    /// assert_eq!(nodes[0].node_index, 0);
+    /// ```
     pub fn set_and_propagate_collected<N: NodeRef<C, G>, M: ModelVariantRef<C>>(
         &mut self,
         node_ref: N,
@@ -265,7 +284,7 @@ impl<C: CoordinateSystem, G: Grid<C>> Generator<C, G> {
         memorized: bool,
     ) -> Result<(GenerationStatus, Vec<GeneratedNode>), NodeSetError> {
         let mut generated_nodes = Vec::new();
-        let node_index = node_ref.to_index(&self.internal.grid);
+        let node_index = self.node_index(&node_ref)?;
         let model_variant_index = model_variant_ref.to_index(&self.internal.rules)?;
         let status = self.internal.set_and_propagate(
             node_index,
@@ -278,7 +297,202 @@ impl<C: CoordinateSystem, G: Grid<C>> Generator<C, G> {
         Ok((status, generated_nodes))
     }
 
-    /// Reinitalizes the generator with the next seed (a seed is generated from the current seed)
+    /// Un-generates the node referenced by `node_ref`, so that it can be generated again. This can be used to undo a [`Generator::set_and_propagate`], memorized or not.
+    ///
+    /// If the node was memorized as an initial node, it is removed from the initial nodes. The possibilities of the nodes are then restored and recomputed from the remaining fixed nodes: all the nodes that were explicitly set or selected, except the unset node, stay generated as they were. Nodes that were only forced by propagation are recomputed.
+    ///
+    /// - Returns `Ok` and the current [`GenerationStatus`] if successful. After a successful call, the generation is always [`GenerationStatus::Ongoing`] unless the remaining fixed nodes force the unset node again.
+    /// - Returns a [`NodeSetError`] if the node reference is invalid, or if the generation was failed and is still failed without this node.
+    ///
+    /// Observers receive a [`GenerationUpdate::Reinitializing`] followed by the nodes generated again.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use procedural_tilemaps_core::prelude::*;
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let (land, water) = (sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(land, vec![land]), (water, vec![water])]);
+    ///
+    /// let mut models = ModelCollection::<Cartesian2D>::new();
+    /// models.create(SocketsCartesian2D::Mono(land));
+    /// models.create(SocketsCartesian2D::Mono(water));
+    ///
+    /// let rules = RulesBuilder::new_cartesian_2d(models, sockets).build().unwrap();
+    /// let grid = CartesianGrid::new_cartesian_2d(8, 8, false, false);
+    /// let mut generator = GeneratorBuilder::new().with_rules(rules).with_grid(grid).build().unwrap();
+    ///
+    /// // Pinning a water tile forces the whole map to water
+    /// generator.set_and_propagate((2, 2), (1, ModelRotation::Rot0), true).unwrap();
+    /// let (_, map) = generator.generate_grid().unwrap();
+    /// assert!(map.iter().all(|instance| instance.model_index == 1));
+    ///
+    /// // The designer changed their mind: land is possible again
+    /// let status = generator.unset((2, 2)).unwrap();
+    /// assert_eq!(status, GenerationStatus::Ongoing);
+    /// assert_eq!(generator.get_models_on(0).len(), 2);
+    /// ```
+    pub fn unset<N: NodeRef<C, G>>(
+        &mut self,
+        node_ref: N,
+    ) -> Result<GenerationStatus, NodeSetError> {
+        self.unset_region([node_ref])
+    }
+
+    /// Same as [`Generator::unset`] but also returns all the [`GeneratedNode`] generated again by this operation if successful.
+    pub fn unset_collected<N: NodeRef<C, G>>(
+        &mut self,
+        node_ref: N,
+    ) -> Result<(GenerationStatus, Vec<GeneratedNode>), NodeSetError> {
+        self.unset_region_collected([node_ref])
+    }
+
+    /// Same as [`Generator::unset`] for multiple nodes at once, which is faster than unsetting them one by one.
+    pub fn unset_region<N: NodeRef<C, G>, I: IntoIterator<Item = N>>(
+        &mut self,
+        node_refs: I,
+    ) -> Result<GenerationStatus, NodeSetError> {
+        let unset_nodes = self.unset_nodes_indexes(node_refs)?;
+        Ok(self
+            .internal
+            .unset_nodes(&unset_nodes, &mut None, &self.initial_nodes)?)
+    }
+
+    /// Same as [`Generator::unset_region`] but also returns all the [`GeneratedNode`] generated again by this operation if successful.
+    pub fn unset_region_collected<N: NodeRef<C, G>, I: IntoIterator<Item = N>>(
+        &mut self,
+        node_refs: I,
+    ) -> Result<(GenerationStatus, Vec<GeneratedNode>), NodeSetError> {
+        let mut generated_nodes = Vec::new();
+        let unset_nodes = self.unset_nodes_indexes(node_refs)?;
+        let status = self.internal.unset_nodes(
+            &unset_nodes,
+            &mut Some(&mut generated_nodes),
+            &self.initial_nodes,
+        )?;
+        Ok((status, generated_nodes))
+    }
+
+    /// Validates the node references and removes them from the memorized initial nodes
+    fn unset_nodes_indexes<N: NodeRef<C, G>, I: IntoIterator<Item = N>>(
+        &mut self,
+        node_refs: I,
+    ) -> Result<HashSet<NodeIndex>, NodeSetError> {
+        let unset_nodes = self.nodes_indexes(node_refs)?;
+        self.initial_nodes
+            .retain(|(node_index, _)| !unset_nodes.contains(node_index));
+        Ok(unset_nodes)
+    }
+
+    fn nodes_indexes<N: NodeRef<C, G>, I: IntoIterator<Item = N>>(
+        &self,
+        node_refs: I,
+    ) -> Result<HashSet<NodeIndex>, NodeSetError> {
+        let mut nodes = HashSet::new();
+        for node_ref in node_refs {
+            let node_index = self.node_index(&node_ref)?;
+            if !self.internal.is_valid_node_index(node_index) {
+                return Err(NodeSetError::InvalidNodeIndex(node_index));
+            }
+            nodes.insert(node_index);
+        }
+        Ok(nodes)
+    }
+
+    fn node_index<N: NodeRef<C, G>>(&self, node_ref: &N) -> Result<NodeIndex, NodeSetError> {
+        node_ref
+            .try_to_index(&self.internal.grid)
+            .ok_or(NodeSetError::InvalidNodeRef)
+    }
+
+    /// Regenerates only the nodes of `region` in a completed `grid_data` (usually the result of a previous generation of this generator), for example to reroll a destroyed area of a map.
+    ///
+    /// All the nodes outside of `region` are fixed to their model in `grid_data` and constrain the nodes of `region`, which are generated again using `rng_mode`. Memorized initial nodes inside `region` are generated again to their model. On a contradiction, the generation of the region is retried up to `max_retry_count` times with the next seeds.
+    ///
+    /// - Returns `Ok` with the merged [`GridData`] and the [`GeneratedNode`] of `region` which changed, in node index order.
+    /// - Returns a [`NodeSetError`] if `grid_data` does not match the grid of the generator, if a node or a model outside of `region` is invalid, or if the region cannot be generated.
+    ///
+    /// After this call, the generator holds the merged result as its current generation.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use procedural_tilemaps_core::prelude::*;
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let (grass, road) = (sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(grass, vec![grass, road]), (road, vec![road])]);
+    ///
+    /// let mut models = ModelCollection::<Cartesian2D>::new();
+    /// models.create(SocketsCartesian2D::Mono(grass));
+    /// models.create(SocketsCartesian2D::Mono(road));
+    ///
+    /// let rules = RulesBuilder::new_cartesian_2d(models, sockets).build().unwrap();
+    /// let grid = CartesianGrid::new_cartesian_2d(16, 16, false, false);
+    /// let mut generator = GeneratorBuilder::new().with_rules(rules).with_grid(grid.clone()).build().unwrap();
+    /// let (_, map) = generator.generate_grid().unwrap();
+    ///
+    /// // Reroll the 4x4 area at (6, 6)
+    /// let village = (6..10).flat_map(|x| (6..10).map(move |y| (x, y)));
+    /// let (new_map, changed_nodes) = generator.regenerate_region(&map, village, RngMode::Seeded(7)).unwrap();
+    ///
+    /// for node in changed_nodes {
+    ///     let pos = grid.pos_from_index(node.node_index);
+    ///     assert!((6..10).contains(&pos.x) && (6..10).contains(&pos.y));
+    /// }
+    /// assert_eq!(new_map.get(0), map.get(0));
+    /// ```
+    pub fn regenerate_region<N: NodeRef<C, G>, I: IntoIterator<Item = N>>(
+        &mut self,
+        grid_data: &GridData<C, ModelInstance, G>,
+        region: I,
+        rng_mode: RngMode,
+    ) -> Result<RegeneratedRegion<C, G>, NodeSetError> {
+        let nodes_count = self.internal.grid.total_size();
+        if grid_data.grid().total_size() != nodes_count {
+            return Err(NodeSetError::InvalidGridSize(
+                grid_data.grid().total_size(),
+                nodes_count,
+            ));
+        }
+        let region = self.nodes_indexes(region)?;
+        let mut fixed_nodes = vec![0; nodes_count];
+        for node_index in grid_data.indexes() {
+            if !region.contains(&node_index) {
+                fixed_nodes[node_index] =
+                    grid_data.get(node_index).to_index(&self.internal.rules)?;
+            }
+        }
+        let seed = match rng_mode {
+            RngMode::Seeded(seed) => seed,
+            RngMode::RandomSeed => rand::random::<u64>(),
+        };
+
+        self.internal.regenerate_nodes(
+            &region,
+            &fixed_nodes,
+            seed,
+            self.max_retry_count,
+            &self.initial_nodes,
+        )?;
+
+        let regenerated = self.internal.to_grid_data();
+        let changed_nodes = grid_data
+            .indexes()
+            .filter(|node_index| {
+                region.contains(node_index)
+                    && regenerated.get(*node_index) != grid_data.get(*node_index)
+            })
+            .map(|node_index| GeneratedNode {
+                node_index,
+                model_instance: *regenerated.get(node_index),
+            })
+            .collect();
+        Ok((regenerated, changed_nodes))
+    }
+
+    /// Reinitializes the generator with the next seed (a seed is generated from the current seed)
     pub fn reinitialize(&mut self) -> GenerationStatus {
         self.internal.reinitialize(&mut None, &self.initial_nodes)
     }
@@ -292,6 +506,26 @@ impl<C: CoordinateSystem, G: Grid<C>> Generator<C, G> {
         (res, generated_nodes)
     }
 
+    /// Reinitializes the generator with the given `seed`
+    pub fn reinitialize_with_seed(&mut self, seed: u64) -> GenerationStatus {
+        self.internal
+            .reinitialize_with_seed(&mut None, seed, &self.initial_nodes)
+    }
+
+    /// Same as [`Generator::reinitialize_with_seed`] but also returns all the [`GeneratedNode`] generated by this generation operation.
+    pub fn reinitialize_with_seed_collected(
+        &mut self,
+        seed: u64,
+    ) -> (GenerationStatus, Vec<GeneratedNode>) {
+        let mut generated_nodes = Vec::new();
+        let res = self.internal.reinitialize_with_seed(
+            &mut Some(&mut generated_nodes),
+            seed,
+            &self.initial_nodes,
+        );
+        (res, generated_nodes)
+    }
+
     /// Returns all the current possible model instances on `node_index`
     pub fn get_models_on(&self, node_index: NodeIndex) -> Vec<ModelInstance> {
         let mut models = Vec::new();
@@ -305,6 +539,17 @@ impl<C: CoordinateSystem, G: Grid<C>> Generator<C, G> {
         models
     }
 
+    /// Returns the weights of all the current possible model instances on `node_index`, in the same order as [`Generator::get_models_on`]
+    pub fn get_weights_on(&self, node_index: NodeIndex) -> Vec<f32> {
+        if !self.internal.is_valid_node_index(node_index) {
+            return Vec::new();
+        }
+        self.internal
+            .possible_model_indexes(node_index)
+            .map(|model_variant_index| self.internal.rules.weight_unchecked(model_variant_index))
+            .collect()
+    }
+
     /// Returns all the current possible model on `node_index` grouped by variations, as well as the total number of possible models
     pub fn get_models_variations_on(&self, node_index: NodeIndex) -> (Vec<ModelVariations>, u32) {
         let mut model_variations = Vec::new();
@@ -326,24 +571,36 @@ impl<C: CoordinateSystem, G: Grid<C>> Generator<C, G> {
                     index: model.model_index,
                     info: self.internal.rules.model_info(model_variant_index),
                     rotations: vec![model.rotation],
+                    flips: vec![model.flip],
                 });
             } else {
                 model_variations[*group_id].rotations.push(model.rotation);
+                model_variations[*group_id].flips.push(model.flip);
             }
         }
 
         (model_variations, total_models_count)
     }
 
-    fn create_observer_queue(&mut self) -> crossbeam_channel::Receiver<GenerationUpdate> {
-        // We can't simply bound to the number of nodes since we might retry some generations. (and send more than number_of_nodes updates)
-        let (sender, receiver) = crossbeam_channel::unbounded();
-        self.internal.observers.push(sender);
+    /// Registers a [`GenerationListener`] which will be called synchronously on generation events. See [`GeneratorBuilder::with_listener`].
+    pub fn add_listener<L: GenerationListener + Send + 'static>(
+        &mut self,
+        listener: Arc<Mutex<L>>,
+    ) {
+        self.internal.add_listener(listener);
+    }
+
+    fn create_observer_queue(
+        &mut self,
+        detailed_updates: DetailedUpdates,
+    ) -> crossbeam_channel::Receiver<GenerationUpdate> {
+        let (queue, receiver) = ObserverQueue::new(detailed_updates);
+        self.internal.add_observer(queue);
         receiver
     }
 }
 
-/// Group of models variaitons based on the same input [crate::generator::model::Model] with different rotations
+/// Group of models variaitons based on the same input [crate::generator::model::Model] with different rotations and reflections
 #[derive(Debug, Clone)]
 pub struct ModelVariations {
     /// Index of the original input model
@@ -352,6 +609,8 @@ pub struct ModelVariations {
     pub info: ModelInfo,
     /// Rotations of the base model
     pub rotations: Vec<ModelRotation>,
+    /// Reflections of the base model, `flips[i]` is applied before `rotations[i]`
+    pub flips: Vec<ModelFlip>,
 }
 
 impl fmt::Display for ModelVariations {
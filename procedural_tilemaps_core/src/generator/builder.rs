@@ -1,4 +1,8 @@
-use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+use std::{
+    collections::HashSet,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
 
 use crate::grid::{
     coordinate_system::CoordinateSystem,
@@ -6,12 +10,16 @@ use crate::grid::{
     grid::{Grid, GridData, NodeRef},
 };
 
-use crate::{GeneratorBuilderError, NodeIndex};
+use crate::{GeneratorBuilderError, NodeIndex, NodeSetError};
 
 use super::{
+    internal_generator::InternalGenerator,
     model::ModelVariantIndex,
     node_heuristic::NodeSelectionHeuristic,
-    observer::{GenerationUpdate, QueuedObserver, QueuedStatefulObserver},
+    observer::{
+        DetailedUpdates, GenerationListener, GenerationNotifiers, ObserverQueue, QueuedObserver,
+        QueuedStatefulObserver,
+    },
     rules::{ModelVariantRef, Rules},
     Collector, GeneratedNode, Generator, ModelSelectionHeuristic, RngMode,
 };
@@ -60,7 +68,7 @@ pub struct GeneratorBuilder<G, R, C: CoordinateSystem, T: Grid<C>> {
     node_selection_heuristic: NodeSelectionHeuristic,
     model_selection_heuristic: ModelSelectionHeuristic,
     rng_mode: RngMode,
-    observers: Vec<crossbeam_channel::Sender<GenerationUpdate>>,
+    notifiers: GenerationNotifiers,
     initial_nodes: Vec<(NodeIndex, ModelVariantIndex)>,
     border_zones: HashSet<(NodeIndex, DirectionIndex)>,
     typestate: PhantomData<(G, R)>,
@@ -76,7 +84,7 @@ impl<C: CoordinateSystem, G: Grid<C>> GeneratorBuilder<Unset, Unset, C, G> {
             node_selection_heuristic: NodeSelectionHeuristic::MinimumRemainingValue,
             model_selection_heuristic: ModelSelectionHeuristic::WeightedProbability,
             rng_mode: RngMode::RandomSeed,
-            observers: Vec::new(),
+            notifiers: GenerationNotifiers::default(),
             initial_nodes: Vec::new(),
             border_zones: HashSet::new(),
             typestate: PhantomData,
@@ -95,7 +103,7 @@ impl<C: CoordinateSystem, G: Grid<C>> GeneratorBuilder<Unset, Unset, C, G> {
             node_selection_heuristic: self.node_selection_heuristic,
             model_selection_heuristic: self.model_selection_heuristic,
             rng_mode: self.rng_mode,
-            observers: self.observers,
+            notifiers: self.notifiers,
             initial_nodes: self.initial_nodes,
             border_zones: self.border_zones,
 
@@ -113,7 +121,7 @@ impl<C: CoordinateSystem, G: Grid<C>> GeneratorBuilder<Unset, Unset, C, G> {
             node_selection_heuristic: self.node_selection_heuristic,
             model_selection_heuristic: self.model_selection_heuristic,
             rng_mode: self.rng_mode,
-            observers: self.observers,
+            notifiers: self.notifiers,
             initial_nodes: self.initial_nodes,
             border_zones: self.border_zones,
 
@@ -133,7 +141,7 @@ impl<C: CoordinateSystem, G: Grid<C>> GeneratorBuilder<Unset, Set, C, G> {
             node_selection_heuristic: self.node_selection_heuristic,
             model_selection_heuristic: self.model_selection_heuristic,
             rng_mode: self.rng_mode,
-            observers: self.observers,
+            notifiers: self.notifiers,
             initial_nodes: self.initial_nodes,
             border_zones: self.border_zones,
 
@@ -164,6 +172,17 @@ impl<G, R, C: CoordinateSystem, T: Grid<C>> GeneratorBuilder<G, R, C, T> {
         self
     }
 
+    /// Registers a [`GenerationListener`] which will be called synchronously by the [`Generator`] on generation events.
+    ///
+    /// The listener is shared: if the builder is cloned, all the generators built from the clones call the same listener.
+    pub fn with_listener<L: GenerationListener + Send + 'static>(
+        mut self,
+        listener: Arc<Mutex<L>>,
+    ) -> Self {
+        self.notifiers.listeners.push(listener);
+        self
+    }
+
     /// Specifies `(node_index, direction_index)` pairs that should skip constraint validation
     /// during support-count initialization.
     ///
@@ -199,8 +218,16 @@ impl<C: CoordinateSystem, R, G: Grid<C>> GeneratorBuilder<Set, R, C, G> {
     ///
     /// Adding the observer before building the generator allows the observer to see the nodes than *can* be generated during a generator's initialization.
     pub fn add_queued_stateful_observer(&mut self) -> QueuedStatefulObserver<C, G> {
-        let (sender, receiver) = crossbeam_channel::unbounded();
-        self.observers.push(sender);
+        self.add_queued_stateful_observer_with_detailed_updates(DetailedUpdates::NONE)
+    }
+
+    /// Same as [`GeneratorBuilder::add_queued_stateful_observer`], but the observer also receives the given [`DetailedUpdates`].
+    pub fn add_queued_stateful_observer_with_detailed_updates(
+        &mut self,
+        detailed_updates: DetailedUpdates,
+    ) -> QueuedStatefulObserver<C, G> {
+        let (queue, receiver) = ObserverQueue::new(detailed_updates);
+        self.notifiers.observers.push(queue);
         let grid = self.grid.clone().unwrap();
         QueuedStatefulObserver::create(receiver, &grid)
     }
@@ -209,8 +236,16 @@ impl<C: CoordinateSystem, R, G: Grid<C>> GeneratorBuilder<Set, R, C, G> {
     ///
     /// Adding the observer before building the generator allows the observer to see the nodes than *can* be generated during a generator's initialization.
     pub fn add_queued_observer(&mut self) -> QueuedObserver {
-        let (sender, receiver) = crossbeam_channel::unbounded();
-        self.observers.push(sender);
+        self.add_queued_observer_with_detailed_updates(DetailedUpdates::NONE)
+    }
+
+    /// Same as [`GeneratorBuilder::add_queued_observer`], but the observer also receives the given [`DetailedUpdates`].
+    pub fn add_queued_observer_with_detailed_updates(
+        &mut self,
+        detailed_updates: DetailedUpdates,
+    ) -> QueuedObserver {
+        let (queue, receiver) = ObserverQueue::new(detailed_updates);
+        self.notifiers.observers.push(queue);
         QueuedObserver::create(receiver)
     }
 
@@ -253,8 +288,11 @@ impl<C: CoordinateSystem, G: Grid<C>> GeneratorBuilder<Set, Set, C, G> {
         let grid = self.grid.as_ref().unwrap();
         let rules = self.rules.as_ref().unwrap();
         for (node_ref, model_ref) in initial_nodes {
+            let node_index = node_ref
+                .try_to_index(grid)
+                .ok_or(NodeSetError::InvalidNodeRef)?;
             self.initial_nodes
-                .push((node_ref.to_index(grid), model_ref.to_index(rules)?));
+                .push((node_index, model_ref.to_index(rules)?));
         }
         Ok(self)
     }
@@ -291,7 +329,7 @@ impl<C: CoordinateSystem, G: Grid<C>> GeneratorBuilder<Set, Set, C, G> {
         self.internal_build(&mut None)
     }
 
-    /// Instantiates a [`Generator`] as specified by the various builder parameters and return the initially generated nodes if any
+    /// Instantiates a [`Generator`] as specified by the various builder parameters and return the initially generated nodes if any, including the initial nodes
     pub fn build_collected(
         self,
     ) -> Result<(Generator<C, G>, Vec<GeneratedNode>), GeneratorBuilderError> {
@@ -307,16 +345,19 @@ impl<C: CoordinateSystem, G: Grid<C>> GeneratorBuilder<Set, Set, C, G> {
         // We know that self.rules and self.grid are `Some` thanks to the typing.
         let rules = self.rules.unwrap();
         let grid = self.grid.unwrap();
-        Ok(Generator::create(
+        let internal = InternalGenerator::new(
             rules,
             grid,
-            self.initial_nodes,
-            self.max_retry_count,
             self.node_selection_heuristic,
             self.model_selection_heuristic,
             self.rng_mode,
-            self.observers,
+            self.notifiers,
             self.border_zones,
+        );
+        Ok(Generator::create(
+            internal,
+            self.initial_nodes,
+            self.max_retry_count,
             collector,
         )?)
     }
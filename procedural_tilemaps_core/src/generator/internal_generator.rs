@@ -1,5 +1,5 @@
 use std::collections::HashSet;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError};
 
 use crate::grid::{
     coordinate_system::CoordinateSystem,
@@ -22,7 +22,10 @@ use crate::{GeneratorError, NodeIndex, NodeSetError};
 use super::{
     model::{ModelInstance, ModelVariantIndex},
     node_heuristic::{InternalNodeSelectionHeuristic, NodeSelectionHeuristic},
-    observer::GenerationUpdate,
+    observer::{
+        DetailedUpdates, GenerationListener, GenerationNotifiers, GenerationPhase,
+        GenerationUpdate, ObserverQueue, SharedListener,
+    },
     rules::Rules,
     Collector, GenInfo, GeneratedNode, GenerationStatus, ModelSelectionHeuristic, NodeSetStatus,
     RngMode,
@@ -53,13 +56,20 @@ pub(crate) struct InternalGenerator<C: CoordinateSystem, G: Grid<C>> {
     pub(crate) status: InternalGeneratorStatus,
     pub(crate) nodes_left_to_generate: usize,
     /// Observers signaled with updates of the nodes.
-    pub(crate) observers: Vec<crossbeam_channel::Sender<GenerationUpdate>>,
+    observers: Vec<ObserverQueue>,
+    /// Detailed updates requested by at least one observer
+    detailed_updates: DetailedUpdates,
+    /// Listeners called synchronously on generation events
+    listeners: Vec<SharedListener>,
+    phase: GenerationPhase,
     pub(crate) seed: u64,
     rng: StdRng,
     /// `nodes[node_index * self.rules.models_count() + model_index]` is true (1) if model with index `model_index` is still allowed on node with index `node_index`
     nodes: BitVec<usize>,
     /// Stores how many models are still possible for a given node
     possible_models_counts: Vec<usize>,
+    /// Models explicitly selected on nodes (by the heuristics or by a set operation), in selection order. Nodes forced by propagation are not included.
+    decisions: Vec<(NodeIndex, ModelVariantIndex)>,
     node_selection_heuristic: InternalNodeSelectionHeuristic,
     model_selection_heuristic: ModelSelectionHeuristic,
 
@@ -87,7 +97,7 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
         node_selection_heuristic: NodeSelectionHeuristic,
         model_selection_heuristic: ModelSelectionHeuristic,
         rng_mode: RngMode,
-        observers: Vec<crossbeam_channel::Sender<GenerationUpdate>>,
+        notifiers: GenerationNotifiers,
         border_zones: HashSet<(usize, DirectionIndex)>,
     ) -> Self {
         let models_count = rules.models_count();
@@ -99,6 +109,16 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
             RngMode::RandomSeed => rand::thread_rng().gen::<u64>(),
         };
 
+        let GenerationNotifiers {
+            observers,
+            listeners,
+        } = notifiers;
+        let detailed_updates = observers
+            .iter()
+            .fold(DetailedUpdates::NONE, |updates, obs| {
+                updates.union(&obs.detailed_updates)
+            });
+
         let node_selection_heuristic = InternalNodeSelectionHeuristic::from_external(
             node_selection_heuristic,
             &rules,
@@ -119,8 +139,12 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
             nodes: bitvec![1; nodes_count * models_count],
             nodes_left_to_generate: nodes_count,
             possible_models_counts: vec![models_count; nodes_count],
+            decisions: Vec::new(),
 
             observers,
+            detailed_updates,
+            listeners,
+            phase: GenerationPhase::Pregen,
 
             propagation_stack: Vec::new(),
             supports_count: Array::zeros((nodes_count, models_count, direction_count)),
@@ -132,6 +156,40 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
 }
 
 impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
+    pub(crate) fn add_observer(&mut self, observer: ObserverQueue) {
+        self.detailed_updates = self.detailed_updates.union(&observer.detailed_updates);
+        self.observers.push(observer);
+    }
+
+    pub(crate) fn add_listener(&mut self, listener: SharedListener) {
+        self.listeners.push(listener);
+    }
+
+    #[inline]
+    fn has_observers(&self) -> bool {
+        !self.observers.is_empty() || !self.listeners.is_empty()
+    }
+
+    /// Sends a detailed update to the observers that subscribed to it. Callers should check [`InternalGenerator::detailed_updates`] first to avoid building the update.
+    fn signal_detailed(&self, update: GenerationUpdate, subscribed: fn(&DetailedUpdates) -> bool) {
+        for obs in self
+            .observers
+            .iter()
+            .filter(|obs| subscribed(&obs.detailed_updates))
+        {
+            let _ = obs.sender.send(update);
+        }
+    }
+
+    fn enter_phase(&mut self, phase: GenerationPhase) {
+        if self.phase != phase {
+            self.phase = phase;
+            if self.detailed_updates.phases {
+                self.signal_detailed(GenerationUpdate::PhaseChanged(phase), |u| u.phases);
+            }
+        }
+    }
+
     #[inline]
     fn is_model_possible(&self, node: NodeIndex, model: ModelVariantIndex) -> bool {
         self.nodes[node * self.rules.models_count() + model] == true
@@ -172,13 +230,18 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
     fn reset_with_seed(&mut self, seed: u64) {
         self.seed = seed;
         self.rng = StdRng::seed_from_u64(seed);
+        self.reset_nodes();
+    }
 
+    /// Resets the generation state of all the nodes, without touching the RNG
+    fn reset_nodes(&mut self) {
         self.status = InternalGeneratorStatus::Ongoing;
 
         let nodes_count = self.grid.total_size();
         self.nodes = bitvec![1;self.rules.models_count() * nodes_count ];
         self.nodes_left_to_generate = nodes_count;
         self.possible_models_counts = vec![self.rules.models_count(); nodes_count];
+        self.decisions.clear();
         self.propagation_stack = Vec::new();
         self.node_selection_heuristic.reinitialize();
     }
@@ -191,7 +254,17 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
     ) -> GenerationStatus {
         // Gen next seed from current rng
         let next_seed = self.rng.gen::<u64>();
-        self.reset_with_seed(next_seed);
+        self.reinitialize_with_seed(collector, next_seed, initial_nodes)
+    }
+
+    /// Resets the generator with the given `seed`
+    pub(crate) fn reinitialize_with_seed(
+        &mut self,
+        collector: &mut Collector,
+        seed: u64,
+        initial_nodes: &Vec<(NodeIndex, ModelVariantIndex)>,
+    ) -> GenerationStatus {
+        self.reset_with_seed(seed);
 
         #[cfg(feature = "debug-traces")]
         info!(
@@ -199,9 +272,7 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
             self.seed, self.status
         );
 
-        for obs in &mut self.observers {
-            let _ = obs.send(GenerationUpdate::Reinitializing(self.seed));
-        }
+        self.signal_reinitializing();
 
         // Since Pre-gen succeeded. The following calls will always succeed.
         let _ = self.initialize_supports_count(collector);
@@ -219,6 +290,8 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
         #[cfg(feature = "debug-traces")]
         debug!("Initializing support counts");
 
+        self.enter_phase(GenerationPhase::Pregen);
+
         let mut neighbours = vec![None; self.grid.directions_count()];
         for node in 0..self.grid.total_size() {
             // For a given `node`, `neighbours[direction]` will hold the optionnal index of the neighbour node in `direction`
@@ -293,6 +366,169 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
         Ok(self.check_if_done())
     }
 
+    /// Resets all the nodes then generates again the initial nodes and the previous decisions that are not on `unset_nodes`. Uses the current seed.
+    ///
+    /// Can only fail if the generation was failed before this call, and the contradiction is not caused by a decision on `unset_nodes`.
+    pub(crate) fn unset_nodes(
+        &mut self,
+        unset_nodes: &HashSet<NodeIndex>,
+        collector: &mut Collector,
+        initial_nodes: &[(NodeIndex, ModelVariantIndex)],
+    ) -> Result<GenerationStatus, GeneratorError> {
+        #[cfg(feature = "debug-traces")]
+        info!("Unsetting {} nodes", unset_nodes.len());
+
+        let decisions = std::mem::take(&mut self.decisions);
+        self.reset_nodes();
+        self.signal_reinitializing();
+
+        // Same as a reinitialization: border zones are only exempted until the remaining nodes are set again
+        let border_zones_active = self.border_zones_active;
+        self.border_zones_active = true;
+        let res = self.replay_nodes(
+            collector,
+            initial_nodes
+                .iter()
+                .chain(decisions.iter())
+                .filter(|(node_index, _)| !unset_nodes.contains(node_index)),
+        );
+        self.border_zones_active = border_zones_active;
+        res
+    }
+
+    /// Fixes all the nodes outside of `region` to their model in `fixed_nodes`, then generates the nodes of `region` starting with `seed`. On a contradiction, retries up to `retry_count` times with the next seeds.
+    ///
+    /// Memorized initial nodes inside the region are generated again to their model before the generation.
+    pub(crate) fn regenerate_nodes(
+        &mut self,
+        region: &HashSet<NodeIndex>,
+        fixed_nodes: &[ModelVariantIndex],
+        seed: u64,
+        retry_count: u32,
+        initial_nodes: &[(NodeIndex, ModelVariantIndex)],
+    ) -> Result<GenInfo, GeneratorError> {
+        #[cfg(feature = "debug-traces")]
+        info!("Regenerating {} nodes with seed {}", region.len(), seed);
+
+        self.reset_with_seed(seed);
+        let mut last_error = None;
+        for try_index in 0..=retry_count {
+            if try_index > 0 {
+                let next_seed = self.rng.gen::<u64>();
+                self.reset_with_seed(next_seed);
+            }
+            self.signal_reinitializing();
+
+            // The fixed nodes do not depend on the seed: if they are not compatible with each other, retrying is pointless.
+            self.border_zones_active = true;
+            match self.fix_nodes_outside(region, fixed_nodes, initial_nodes)? {
+                GenerationStatus::Ongoing => (),
+                GenerationStatus::Done => {
+                    return Ok(GenInfo {
+                        try_count: try_index + 1,
+                    })
+                }
+            }
+
+            self.border_zones_active = false;
+            match self.generate_remaining_nodes(&mut None) {
+                Ok(_) => {
+                    return Ok(GenInfo {
+                        try_count: try_index + 1,
+                    })
+                }
+                Err(err) => {
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.unwrap()) // We know that last_err is Some
+    }
+
+    /// Sets all the nodes outside of `region` to their model in `fixed_nodes`, then sets the initial nodes inside `region`.
+    ///
+    /// Similar to border zones: the fixed nodes come from a valid generation so they do not need to constrain each other. Only the removals of the fixed nodes adjacent to the region are propagated.
+    fn fix_nodes_outside(
+        &mut self,
+        region: &HashSet<NodeIndex>,
+        fixed_nodes: &[ModelVariantIndex],
+        initial_nodes: &[(NodeIndex, ModelVariantIndex)],
+    ) -> Result<GenerationStatus, GeneratorError> {
+        self.initialize_supports_count(&mut None)?;
+
+        let mut neighbours = vec![None; self.grid.directions_count()];
+        let mut region_border: HashSet<NodeIndex> = HashSet::new();
+        for &node_index in region {
+            self.grid
+                .get_neighbours_in_all_directions(node_index, &mut neighbours);
+            region_border.extend(
+                neighbours
+                    .iter()
+                    .flatten()
+                    .filter(|neighbour| !region.contains(*neighbour)),
+            );
+        }
+
+        for (node_index, &model_variant_index) in fixed_nodes.iter().enumerate() {
+            if region.contains(&node_index) {
+                continue;
+            }
+            if !self.is_model_possible(node_index, model_variant_index) {
+                self.signal_contradiction(node_index);
+                return Err(GeneratorError { node_index });
+            }
+            if self.possible_models_counts[node_index] > 1 {
+                self.signal_selection(&mut None, node_index, model_variant_index);
+                self.handle_selected(node_index, model_variant_index);
+            }
+        }
+        self.propagation_stack
+            .retain(|entry| region_border.contains(&entry.node_index));
+        if let Err(err) = self.propagate(&mut None) {
+            self.signal_contradiction(err.node_index);
+            return Err(err);
+        };
+
+        for &(node_index, model_variant_index) in initial_nodes {
+            if !region.contains(&node_index) || self.possible_models_counts[node_index] <= 1 {
+                continue;
+            }
+            if !self.is_model_possible(node_index, model_variant_index) {
+                self.signal_contradiction(node_index);
+                return Err(GeneratorError { node_index });
+            }
+            match self.unchecked_set_and_propagate(node_index, model_variant_index, &mut None)? {
+                GenerationStatus::Ongoing => (),
+                GenerationStatus::Done => return Ok(GenerationStatus::Done),
+            }
+        }
+        Ok(self.check_if_done())
+    }
+
+    /// Initializes the supports and sets the given nodes. Nodes already generated are skipped.
+    fn replay_nodes<'a, I: Iterator<Item = &'a (NodeIndex, ModelVariantIndex)>>(
+        &mut self,
+        collector: &mut Collector,
+        nodes: I,
+    ) -> Result<GenerationStatus, GeneratorError> {
+        self.initialize_supports_count(collector)?;
+        for &(node_index, model_variant_index) in nodes {
+            if self.possible_models_counts[node_index] <= 1 {
+                continue;
+            }
+            // Constraints are only removed, so a model selected before is still possible, unless the decisions come from a failed generation.
+            if !self.is_model_possible(node_index, model_variant_index) {
+                self.signal_contradiction(node_index);
+                return Err(GeneratorError { node_index });
+            }
+            match self.unchecked_set_and_propagate(node_index, model_variant_index, collector)? {
+                GenerationStatus::Ongoing => (),
+                GenerationStatus::Done => return Ok(GenerationStatus::Done),
+            }
+        }
+        Ok(self.check_if_done())
+    }
+
     pub(crate) fn pregen(
         &mut self,
         collector: &mut Collector,
@@ -462,7 +698,7 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
             self.grid.pos_from_index(node_index)
         );
 
-        if !self.observers.is_empty() {
+        if self.has_observers() || collector.is_some() {
             self.signal_selection(collector, node_index, model_variant_index);
         }
 
@@ -480,6 +716,7 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
         &mut self,
         collector: &mut Collector,
     ) -> Result<GenerationStatus, GeneratorError> {
+        self.enter_phase(GenerationPhase::Generation);
         let node_index = match self
             .node_selection_heuristic
             .select_node(&self.possible_models_counts, &mut self.rng)
@@ -502,7 +739,7 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
             node_index,
             self.grid.pos_from_index(node_index)
         );
-        if !self.observers.is_empty() || collector.is_some() {
+        if self.has_observers() || collector.is_some() {
             self.signal_selection(collector, node_index, selected_model_index);
         }
 
@@ -548,6 +785,7 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
 
             // Enqueue removal for propagation
             self.enqueue_removal_to_propagate(node_index, model_index);
+            self.signal_ban(node_index, model_index);
 
             // None of these model are possible on this node now, set their support to 0
             for dir in 0..self.grid.directions_count() {
@@ -565,7 +803,19 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
         }
         self.nodes
             .set(node_index * models_count + selected_model_index, true);
+        if self.detailed_updates.possibilities_counts
+            && self.possible_models_counts[node_index] != 1
+        {
+            self.signal_detailed(
+                GenerationUpdate::PossibilitiesCountChanged {
+                    node_index,
+                    count: 1,
+                },
+                |u| u.possibilities_counts,
+            );
+        }
         self.possible_models_counts[node_index] = 1;
+        self.decisions.push((node_index, selected_model_index));
     }
 
     /// Returns [`GeneratorError`] if the node has no possible models left. Else, returns `Ok`.
@@ -590,6 +840,18 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
 
         let number_of_models_left = &mut self.possible_models_counts[node_index];
         *number_of_models_left = number_of_models_left.saturating_sub(1);
+        let number_of_models_left = *number_of_models_left;
+
+        self.signal_ban(node_index, model);
+        if self.detailed_updates.possibilities_counts {
+            self.signal_detailed(
+                GenerationUpdate::PossibilitiesCountChanged {
+                    node_index,
+                    count: number_of_models_left,
+                },
+                |u| u.possibilities_counts,
+            );
+        }
 
         self.node_selection_heuristic.handle_ban(
             node_index,
@@ -607,7 +869,7 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
             number_of_models_left
         );
 
-        match *number_of_models_left {
+        match number_of_models_left {
             0 => return Err(GeneratorError { node_index }),
             1 => {
                 #[cfg(feature = "debug-traces")]
@@ -623,7 +885,7 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
                 }
 
                 // Check beforehand to avoid `get_model_index` call
-                if !self.observers.is_empty() || collector.is_some() {
+                if self.has_observers() || collector.is_some() {
                     self.signal_selection(collector, node_index, self.get_model_index(node_index));
                 }
             }
@@ -654,6 +916,16 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
     ///
     /// Does not modify the generator internal status.
     fn propagate(&mut self, collector: &mut Collector) -> Result<(), GeneratorError> {
+        if !self.detailed_updates.propagation || self.propagation_stack.is_empty() {
+            return self.propagate_stack(collector);
+        }
+        self.signal_detailed(GenerationUpdate::PropagationStarted, |u| u.propagation);
+        let res = self.propagate_stack(collector);
+        self.signal_detailed(GenerationUpdate::PropagationEnded, |u| u.propagation);
+        res
+    }
+
+    fn propagate_stack(&mut self, collector: &mut Collector) -> Result<(), GeneratorError> {
         // Clone the ref to allow for mutability of other members in the interior loops
         let rules = Arc::clone(&self.rules);
 
@@ -708,8 +980,9 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
         };
         let update = GenerationUpdate::Generated(grid_node);
         for obs in &mut self.observers {
-            let _ = obs.send(update);
+            let _ = obs.sender.send(update);
         }
+        self.signal_listeners(|listener| listener.on_generated(grid_node));
         if let Some(collector) = collector {
             collector.push(grid_node);
         }
@@ -722,7 +995,38 @@ impl<C: CoordinateSystem, G: Grid<C>> InternalGenerator<C, G> {
 
         self.status = InternalGeneratorStatus::Failed(GeneratorError { node_index });
         for obs in &mut self.observers {
-            let _ = obs.send(GenerationUpdate::Failed(node_index));
+            let _ = obs.sender.send(GenerationUpdate::Failed(node_index));
+        }
+        self.signal_listeners(|listener| listener.on_contradiction(node_index));
+    }
+
+    fn signal_reinitializing(&mut self) {
+        for obs in &mut self.observers {
+            let _ = obs.sender.send(GenerationUpdate::Reinitializing(self.seed));
+        }
+        let seed = self.seed;
+        self.signal_listeners(|listener| listener.on_reinitialize(seed));
+    }
+
+    #[inline]
+    fn signal_ban(&self, node_index: NodeIndex, model_variant_index: ModelVariantIndex) {
+        if self.detailed_updates.bans {
+            self.signal_detailed(
+                GenerationUpdate::Banned {
+                    node_index,
+                    model_variant_index,
+                },
+                |u| u.bans,
+            );
+        }
+        self.signal_listeners(|listener| listener.on_ban(node_index, model_variant_index));
+    }
+
+    /// Calls `signal` on all the listeners. A listener whose mutex was poisoned (by a panic while it was locked) is still called.
+    #[inline]
+    fn signal_listeners(&self, signal: impl Fn(&mut (dyn GenerationListener + Send))) {
+        for listener in &self.listeners {
+            signal(&mut *listener.lock().unwrap_or_else(PoisonError::into_inner));
         }
     }
 
@@ -1,9 +1,20 @@
-use std::{borrow::Cow, collections::HashSet, fmt, marker::PhantomData};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt,
+    marker::PhantomData,
+    str::FromStr,
+};
 
-use crate::grid::{
-    cartesian::coordinates::{Cartesian2D, Cartesian3D},
-    coordinate_system::CoordinateSystem,
-    direction::{Direction, DirectionTrait},
+use crate::{
+    grid::{
+        cartesian::coordinates::{Cartesian2D, Cartesian3D},
+        coordinate_system::CoordinateSystem,
+        direction::{Direction, DirectionTrait},
+        graph::coordinates::GraphCoordinates,
+        hexagonal::coordinates::Hexagonal,
+    },
+    RulesBuilderError,
 };
 #[cfg(feature = "debug-traces")]
 use tracing::warn;
@@ -15,7 +26,10 @@ use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
 
 use super::{
     rules::CARTESIAN_2D_ROTATION_AXIS,
-    socket::{Socket, SocketId, SocketsCartesian2D, SocketsCartesian3D},
+    socket::{
+        Socket, SocketCollection, SocketId, SocketsCartesian2D, SocketsCartesian3D, SocketsGraph,
+        SocketsHexagonal,
+    },
 };
 
 /// Index of an original model
@@ -27,6 +41,97 @@ pub type ModelVariantIndex = usize;
 /// Default weight of [`Model`] and [`ModelTemplate`]
 pub const DEFAULT_MODEL_WEIGHT: f32 = 1.0;
 
+/// Tags and key/value metadata of a [`Model`], carried over to the [`super::rules::Rules`] built from it.
+///
+/// Neither is used during the generation: they allow gameplay code to query properties (walkable, water, movement cost, ...) of the generated nodes, see [`super::rules::Rules::models_with_tag`] and [`crate::grid::GridData::tag_mask`].
+///
+/// ### Example
+/// ```
+/// use procedural_tilemaps_core::generator::model::ModelMetadata;
+///
+/// let metadata = ModelMetadata::new()
+///     .with_tag("water")
+///     .with_value("cost", "3");
+/// assert!(metadata.has_tag("water"));
+/// assert_eq!(metadata.get("cost"), Some("3"));
+/// assert_eq!(metadata.get_parsed::<u32>("cost"), Some(3));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub struct ModelMetadata {
+    tags: BTreeSet<Cow<'static, str>>,
+    values: BTreeMap<Cow<'static, str>, Cow<'static, str>>,
+}
+
+impl ModelMetadata {
+    /// Creates a new [`ModelMetadata`] without any tag or value
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the tag `tag`
+    pub fn with_tag(mut self, tag: impl Into<Cow<'static, str>>) -> Self {
+        self.insert_tag(tag);
+        self
+    }
+
+    /// Sets the value of `key` to `value`
+    pub fn with_value(
+        mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.insert_value(key, value);
+        self
+    }
+
+    /// Adds the tag `tag`. Returns `false` if it was already present.
+    pub fn insert_tag(&mut self, tag: impl Into<Cow<'static, str>>) -> bool {
+        self.tags.insert(tag.into())
+    }
+
+    /// Sets the value of `key` to `value`, returning the previous value if any
+    pub fn insert_value(
+        &mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> Option<Cow<'static, str>> {
+        self.values.insert(key.into(), value.into())
+    }
+
+    /// Returns `true` if the tag `tag` is present
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Returns an iterator over all the tags, in lexicographic order
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|tag| tag.as_ref())
+    }
+
+    /// Returns the value of `key`, if any
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|value| value.as_ref())
+    }
+
+    /// Returns the value of `key` parsed as a `T`. Returns [`None`] if there is no such value or if it cannot be parsed.
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|value| value.parse().ok())
+    }
+
+    /// Returns an iterator over all the key/value pairs, in lexicographic order of the keys
+    pub fn values(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.as_ref()))
+    }
+
+    /// Returns `true` if there are no tags and no values
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.values.is_empty()
+    }
+}
+
 #[derive(Clone, Debug)]
 /// Most of the information about a [`Model`] (but notably without any [`ModelIndex`]).
 ///
@@ -46,6 +151,20 @@ pub struct ModelTemplate<C> {
     /// - In 3d, sockets of a model that are on the rotation axis are rotated into new sockets when the model itself is rotated. See [`crate::generator::socket::SocketCollection`] for how to define and/or constrain sockets connections on the rotation axis.
     /// - In 2d, the rotation axis cannot be modified and is set to [`Direction::ZForward`].
     allowed_rotations: HashSet<ModelRotation>,
+    /// Allowed reflections of this [`ModelTemplate`] in the output, in the plane orthogonal to the rotation axis specified in the rules.
+    ///
+    /// Defaults to only [`ModelFlip::None`].
+    ///
+    /// Reflections are combined with the allowed rotations. Combinations that result in the same transformation (such as [`ModelFlip::XY`] and [`ModelRotation::Rot180`]) only create one variation.
+    allowed_flips: HashSet<ModelFlip>,
+    /// Allowed 60° rotations of this [`ModelTemplate`] in the output. Only used by hexagonal rules.
+    ///
+    /// Defaults to only [`HexRotation::Rot0`].
+    allowed_hex_rotations: HashSet<HexRotation>,
+    /// Tags and key/value metadata of this [`ModelTemplate`], shared by all its variations.
+    ///
+    /// Defaults to no tags and no metadata.
+    metadata: ModelMetadata,
     typestate: PhantomData<C>,
 }
 
@@ -54,7 +173,10 @@ impl ModelTemplate<Cartesian3D> {
         Self {
             sockets: sockets.into(),
             allowed_rotations: HashSet::from([ModelRotation::Rot0]),
+            allowed_flips: HashSet::from([ModelFlip::None]),
+            allowed_hex_rotations: HashSet::from([HexRotation::Rot0]),
             weight: DEFAULT_MODEL_WEIGHT,
+            metadata: ModelMetadata::default(),
             typestate: PhantomData,
         }
     }
@@ -65,6 +187,9 @@ impl ModelTemplate<Cartesian3D> {
             sockets: self.rotated_sockets(rotation, axis),
             weight: self.weight,
             allowed_rotations: self.allowed_rotations.clone(),
+            allowed_flips: self.allowed_flips.clone(),
+            allowed_hex_rotations: self.allowed_hex_rotations.clone(),
+            metadata: self.metadata.clone(),
             typestate: PhantomData,
         }
     }
@@ -75,7 +200,10 @@ impl ModelTemplate<Cartesian2D> {
         Self {
             sockets: sockets.into(),
             allowed_rotations: HashSet::from([ModelRotation::Rot0]),
+            allowed_flips: HashSet::from([ModelFlip::None]),
+            allowed_hex_rotations: HashSet::from([HexRotation::Rot0]),
             weight: DEFAULT_MODEL_WEIGHT,
+            metadata: ModelMetadata::default(),
             typestate: PhantomData,
         }
     }
@@ -86,6 +214,63 @@ impl ModelTemplate<Cartesian2D> {
             sockets: self.rotated_sockets(rotation, CARTESIAN_2D_ROTATION_AXIS),
             weight: self.weight,
             allowed_rotations: self.allowed_rotations.clone(),
+            allowed_flips: self.allowed_flips.clone(),
+            allowed_hex_rotations: self.allowed_hex_rotations.clone(),
+            metadata: self.metadata.clone(),
+            typestate: PhantomData,
+        }
+    }
+}
+
+impl ModelTemplate<Hexagonal> {
+    pub(crate) fn new(sockets: SocketsHexagonal) -> ModelTemplate<Hexagonal> {
+        Self {
+            sockets: sockets.into(),
+            allowed_rotations: HashSet::from([ModelRotation::Rot0]),
+            allowed_flips: HashSet::from([ModelFlip::None]),
+            allowed_hex_rotations: HashSet::from([HexRotation::Rot0]),
+            weight: DEFAULT_MODEL_WEIGHT,
+            metadata: ModelMetadata::default(),
+            typestate: PhantomData,
+        }
+    }
+
+    /// Returns a clone of the [`Model`] with its sockets rotated by `rotation`.
+    ///
+    /// To let the [`super::rules::Rules`] create the rotated variations of a model instead, see [`ModelTemplate::<Hexagonal>::with_hex_rotations`].
+    pub fn rotated(&self, rotation: HexRotation) -> Self {
+        let mut rotated_sockets = self.sockets.clone();
+        // Directions are ordered counter-clockwise, each one 60° apart
+        rotated_sockets.rotate_right(rotation.index() as usize);
+        Self {
+            sockets: rotated_sockets,
+            weight: self.weight,
+            allowed_rotations: self.allowed_rotations.clone(),
+            allowed_flips: self.allowed_flips.clone(),
+            allowed_hex_rotations: self.allowed_hex_rotations.clone(),
+            metadata: self.metadata.clone(),
+            typestate: PhantomData,
+        }
+    }
+
+    /// Specify that this [`ModelTemplate`] can be rotated in every way specified in `rotations`, by steps of 60°.
+    ///
+    /// Rotations are specified as counter-clockwise
+    pub fn with_hex_rotations<R: Into<HashSet<HexRotation>>>(mut self, rotations: R) -> Self {
+        self.allowed_hex_rotations = rotations.into();
+        self
+    }
+}
+
+impl ModelTemplate<GraphCoordinates> {
+    pub(crate) fn new(sockets: SocketsGraph) -> ModelTemplate<GraphCoordinates> {
+        Self {
+            sockets: sockets.into(),
+            allowed_rotations: HashSet::from([ModelRotation::Rot0]),
+            allowed_flips: HashSet::from([ModelFlip::None]),
+            allowed_hex_rotations: HashSet::from([HexRotation::Rot0]),
+            weight: DEFAULT_MODEL_WEIGHT,
+            metadata: ModelMetadata::default(),
             typestate: PhantomData,
         }
     }
@@ -125,9 +310,25 @@ impl<C: CoordinateSystem> ModelTemplate<C> {
     }
     /// Specify that this [`ModelTemplate`] can be rotated in every way.
     ///
-    /// Rotations are specified as counter-clockwise
+    /// Rotations are specified as counter-clockwise. Hexagonal models are allowed the six [`HexRotation`]s.
     pub fn with_all_rotations(mut self) -> Self {
         self.allowed_rotations = ALL_MODEL_ROTATIONS.iter().cloned().collect();
+        self.allowed_hex_rotations = ALL_HEX_ROTATIONS.iter().cloned().collect();
+        self
+    }
+
+    /// Specify that this [`ModelTemplate`] can be reflected in every way specified in `flips`.
+    ///
+    /// Reflections are applied before the rotations
+    pub fn with_flips<F: Into<HashSet<ModelFlip>>>(mut self, flips: F) -> Self {
+        self.allowed_flips = flips.into();
+        self
+    }
+    /// Specify that this [`ModelTemplate`] can be reflected in every way.
+    ///
+    /// Combined with [`ModelTemplate::with_all_rotations`], this covers every symmetry of a square tile.
+    pub fn with_reflections(mut self) -> Self {
+        self.allowed_flips = ALL_MODEL_FLIPS.iter().cloned().collect();
         self
     }
 
@@ -151,29 +352,99 @@ impl<C: CoordinateSystem> ModelTemplate<C> {
         self
     }
 
+    /// Adds the tag `tag` to this [`ModelTemplate`]. See [`ModelMetadata`].
+    ///
+    /// All the variations of this [`ModelTemplate`] will share the same tags.
+    pub fn with_tag(mut self, tag: impl Into<Cow<'static, str>>) -> Self {
+        self.metadata.insert_tag(tag);
+        self
+    }
+
+    /// Adds all the tags in `tags` to this [`ModelTemplate`]. See [`ModelMetadata`].
+    pub fn with_tags<T: Into<Cow<'static, str>>>(
+        mut self,
+        tags: impl IntoIterator<Item = T>,
+    ) -> Self {
+        for tag in tags {
+            self.metadata.insert_tag(tag);
+        }
+        self
+    }
+
+    /// Sets the metadata value of `key` to `value` for this [`ModelTemplate`]. See [`ModelMetadata`].
+    pub fn with_metadata(
+        mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.metadata.insert_value(key, value);
+        self
+    }
+
+    /// Returns the tags and metadata of this [`ModelTemplate`]
+    pub fn metadata(&self) -> &ModelMetadata {
+        &self.metadata
+    }
+
     fn rotated_sockets(&self, rotation: ModelRotation, rot_axis: C::Direction) -> Vec<Vec<Socket>> {
-        let mut rotated_sockets = vec![Vec::new(); self.sockets.len()];
-
-        // Not pretty: if the node sockets contain the rotation axis
-        if self.sockets.len() > rot_axis.into() {
-            // Sockets on the rotation axis are marked as rotated
-            for fixed_axis in [rot_axis, rot_axis.opposite()] {
-                rotated_sockets[fixed_axis.into()].extend(self.sockets[fixed_axis.into()].clone());
-                for socket in &mut rotated_sockets[fixed_axis.into()] {
-                    socket.rotate(rotation);
-                }
+        rotate_sockets(&self.sockets, rotation, rot_axis)
+    }
+
+    /// Sockets of this template reflected by `flip` then rotated by `rotation`.
+    fn transformed_sockets(
+        &self,
+        flip: ModelFlip,
+        rotation: ModelRotation,
+        rot_axis: C::Direction,
+        socket_collection: &SocketCollection,
+    ) -> Vec<Vec<Socket>> {
+        let (flip, rotation) = flip.canonical(rotation);
+        match flip {
+            // Coordinate systems without a rotation basis (such as graphs) can only use the untransformed sockets
+            ModelFlip::None if rotation == ModelRotation::Rot0 => self.sockets.clone(),
+            ModelFlip::None => self.rotated_sockets(rotation, rot_axis),
+            _ => {
+                // Reflecting a model reverses the reading order of all of its sides
+                let mut mirrored_sockets: Vec<Vec<Socket>> = self
+                    .sockets
+                    .iter()
+                    .map(|dir| dir.iter().map(|s| socket_collection.mirrored(s)).collect())
+                    .collect();
+                // `canonical` only returns `ModelFlip::None` or `ModelFlip::X`
+                let basis = rot_axis.rotation_basis();
+                mirrored_sockets.swap(basis[0].into(), basis[2].into());
+                rotate_sockets(&mirrored_sockets, rotation, rot_axis)
             }
         }
+    }
+}
 
-        let basis = rot_axis.rotation_basis();
-        let mut rotated_basis = basis.to_vec();
-        rotated_basis.rotate_right(rotation.index() as usize);
+fn rotate_sockets<D: DirectionTrait + 'static>(
+    sockets: &[Vec<Socket>],
+    rotation: ModelRotation,
+    rot_axis: D,
+) -> Vec<Vec<Socket>> {
+    let mut rotated_sockets = vec![Vec::new(); sockets.len()];
 
-        for i in 0..basis.len() {
-            rotated_sockets[basis[i].into()].extend(self.sockets[rotated_basis[i].into()].clone());
+    // Not pretty: if the node sockets contain the rotation axis
+    if sockets.len() > rot_axis.into() {
+        // Sockets on the rotation axis are marked as rotated
+        for fixed_axis in [rot_axis, rot_axis.opposite()] {
+            rotated_sockets[fixed_axis.into()].extend(sockets[fixed_axis.into()].clone());
+            for socket in &mut rotated_sockets[fixed_axis.into()] {
+                socket.rotate(rotation);
+            }
         }
-        rotated_sockets
     }
+
+    let basis = rot_axis.rotation_basis();
+    let mut rotated_basis = basis.to_vec();
+    rotated_basis.rotate_right(rotation.index() as usize);
+
+    for i in 0..basis.len() {
+        rotated_sockets[basis[i].into()].extend(sockets[rotated_basis[i].into()].clone());
+    }
+    rotated_sockets
 }
 
 /// Used to create one or more [`Model`]. Created models can then be used in a [`super::rules::RulesBuilder`]
@@ -222,13 +493,35 @@ impl<C: CoordinateSystem> ModelCollection<C> {
         self.models.last_mut()
     }
 
-    pub(crate) fn create_variations(&self, rotation_axis: C::Direction) -> Vec<ModelVariation> {
+    pub(crate) fn create_variations(
+        &self,
+        rotation_axis: C::Direction,
+        socket_collection: &SocketCollection,
+    ) -> Result<Vec<ModelVariation>, RulesBuilderError> {
         let mut model_variations = Vec::new();
+        // Rotations and reflections are defined on a square basis. Hexagonal models only support their 60° rotations, and other coordinate systems (such as graphs) only support the untransformed models.
+        let rotation_basis = rotation_axis.rotation_basis();
+        let transformable = rotation_basis.len() == ALL_MODEL_ROTATIONS.len();
+        let hexagonal = rotation_basis.len() == ALL_HEX_ROTATIONS.len();
         for model in self.models.iter() {
-            // Iterate on a vec of all possible node rotations and filter with the set to have a deterministic insertion order of model variations.
-            for rotation in ALL_MODEL_ROTATIONS {
-                if model.template.allowed_rotations.contains(&rotation) {
-                    let rotated_sockets = model.template.rotated_sockets(*rotation, rotation_axis);
+            let all_rotations: HashSet<ModelRotation> =
+                ALL_MODEL_ROTATIONS.iter().cloned().collect();
+            let untransformed = model.template.allowed_flips == HashSet::from([ModelFlip::None])
+                && (model.template.allowed_rotations == HashSet::from([ModelRotation::Rot0])
+                    // Set by `with_all_rotations`, along with all the hexagonal rotations
+                    || (hexagonal && model.template.allowed_rotations == all_rotations));
+            if !transformable && !untransformed {
+                return Err(RulesBuilderError::UnsupportedTransformations(model.index));
+            }
+            if hexagonal {
+                // Iterate on all possible rotations and filter with the set to have a deterministic insertion order of model variations.
+                for rotation in ALL_HEX_ROTATIONS {
+                    if !model.template.allowed_hex_rotations.contains(rotation) {
+                        continue;
+                    }
+                    // Directions are ordered counter-clockwise, each one 60° apart
+                    let mut rotated_sockets = model.template.sockets.clone();
+                    rotated_sockets.rotate_right(rotation.index() as usize);
                     model_variations.push(ModelVariation {
                         sockets: rotated_sockets
                             .iter()
@@ -236,14 +529,51 @@ impl<C: CoordinateSystem> ModelCollection<C> {
                             .collect(),
                         weight: model.template.weight,
                         original_index: model.index,
+                        rotation: ModelRotation::Rot0,
+                        flip: ModelFlip::None,
+                        hex_rotation: *rotation,
+                        #[cfg(feature = "models-names")]
+                        name: model.name.clone(),
+                    });
+                }
+                continue;
+            }
+            // Some reflections are equivalent to a rotation of another reflection. Only keep the first one encountered.
+            let mut transformations = HashSet::new();
+            // Iterate on a vec of all possible node flips & rotations and filter with the sets to have a deterministic insertion order of model variations.
+            for flip in ALL_MODEL_FLIPS {
+                if !model.template.allowed_flips.contains(flip) {
+                    continue;
+                }
+                for rotation in ALL_MODEL_ROTATIONS {
+                    if !model.template.allowed_rotations.contains(&rotation)
+                        || !transformations.insert(flip.canonical(*rotation))
+                    {
+                        continue;
+                    }
+                    let transformed_sockets = model.template.transformed_sockets(
+                        *flip,
+                        *rotation,
+                        rotation_axis,
+                        socket_collection,
+                    );
+                    model_variations.push(ModelVariation {
+                        sockets: transformed_sockets
+                            .iter()
+                            .map(|dir| dir.iter().map(|s| s.id()).collect())
+                            .collect(),
+                        weight: model.template.weight,
+                        original_index: model.index,
                         rotation: *rotation,
+                        flip: *flip,
+                        hex_rotation: HexRotation::Rot0,
                         #[cfg(feature = "models-names")]
                         name: model.name.clone(),
                     });
                 }
             }
         }
-        model_variations
+        Ok(model_variations)
     }
 }
 
@@ -308,9 +638,25 @@ impl<C: CoordinateSystem> Model<C> {
     }
     /// Specify that this [`Model`] can be rotated in every way.
     ///
-    /// Rotations are specified as counter-clockwise
+    /// Rotations are specified as counter-clockwise. Hexagonal models are allowed the six [`HexRotation`]s.
     pub fn with_all_rotations(&mut self) -> &mut Self {
         self.template.allowed_rotations = ALL_MODEL_ROTATIONS.iter().cloned().collect();
+        self.template.allowed_hex_rotations = ALL_HEX_ROTATIONS.iter().cloned().collect();
+        self
+    }
+
+    /// Specify that this [`Model`] can be reflected in every way specified in `flips`.
+    ///
+    /// Reflections are applied before the rotations
+    pub fn with_flips<F: Into<HashSet<ModelFlip>>>(&mut self, flips: F) -> &mut Self {
+        self.template.allowed_flips = flips.into();
+        self
+    }
+    /// Specify that this [`Model`] can be reflected in every way.
+    ///
+    /// Combined with [`Model::with_all_rotations`], this covers every symmetry of a square tile.
+    pub fn with_reflections(&mut self) -> &mut Self {
+        self.template.allowed_flips = ALL_MODEL_FLIPS.iter().cloned().collect();
         self
     }
 
@@ -346,6 +692,40 @@ impl<C: CoordinateSystem> Model<C> {
         self
     }
 
+    /// Adds the tag `tag` to this [`Model`]. See [`ModelMetadata`].
+    ///
+    /// All the variations of this [`Model`] will share the same tags.
+    pub fn with_tag(&mut self, tag: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.template.metadata.insert_tag(tag);
+        self
+    }
+
+    /// Adds all the tags in `tags` to this [`Model`]. See [`ModelMetadata`].
+    pub fn with_tags<T: Into<Cow<'static, str>>>(
+        &mut self,
+        tags: impl IntoIterator<Item = T>,
+    ) -> &mut Self {
+        for tag in tags {
+            self.template.metadata.insert_tag(tag);
+        }
+        self
+    }
+
+    /// Sets the metadata value of `key` to `value` for this [`Model`]. See [`ModelMetadata`].
+    pub fn with_metadata(
+        &mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> &mut Self {
+        self.template.metadata.insert_value(key, value);
+        self
+    }
+
+    /// Returns the tags and metadata of this [`Model`]
+    pub fn metadata(&self) -> &ModelMetadata {
+        &self.template.metadata
+    }
+
     pub(crate) fn first_rot(&self) -> ModelRotation {
         for rot in ALL_MODEL_ROTATIONS {
             if self.template.allowed_rotations.contains(rot) {
@@ -355,21 +735,69 @@ impl<C: CoordinateSystem> Model<C> {
         ModelRotation::Rot0
     }
 
+    pub(crate) fn first_flip(&self) -> ModelFlip {
+        for flip in ALL_MODEL_FLIPS {
+            if self.template.allowed_flips.contains(flip) {
+                return *flip;
+            }
+        }
+        ModelFlip::None
+    }
+
+    pub(crate) fn first_hex_rot(&self) -> HexRotation {
+        for rot in ALL_HEX_ROTATIONS {
+            if self.template.allowed_hex_rotations.contains(rot) {
+                return *rot;
+            }
+        }
+        HexRotation::Rot0
+    }
+
     /// Creates a model instance from this model
     pub fn instance(&self) -> ModelInstance {
         ModelInstance {
             model_index: self.index,
             rotation: self.first_rot(),
+            flip: self.first_flip(),
+            hex_rotation: self.first_hex_rot(),
         }
     }
 }
+
+impl Model<Hexagonal> {
+    /// Specify that this [`Model`] can be rotated in every way specified in `rotations`, by steps of 60°.
+    ///
+    /// Rotations are specified as counter-clockwise
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::generator::{model::{HexRotation, ModelCollection}, rules::RulesBuilder, socket::{SocketCollection, SocketsHexagonal}};
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let (road, grass) = (sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(road, vec![road]), (grass, vec![grass])]);
+    /// let mut models = ModelCollection::new();
+    /// // A road going from the east to the north-west
+    /// models
+    ///     .create(SocketsHexagonal::Simple { east: road, north_east: grass, north_west: road, west: grass, south_west: grass, south_east: grass })
+    ///     .with_hex_rotations([HexRotation::Rot0, HexRotation::Rot60]);
+    ///
+    /// let rules = RulesBuilder::new_hexagonal(models, sockets).build().unwrap();
+    /// assert_eq!(rules.models_count(), 2);
+    /// assert_eq!(rules.hex_variant_index(0, HexRotation::Rot60), Some(1));
+    /// ```
+    pub fn with_hex_rotations<R: Into<HashSet<HexRotation>>>(&mut self, rotations: R) -> &mut Self {
+        self.template.allowed_hex_rotations = rotations.into();
+        self
+    }
+}
 impl<C: CoordinateSystem> Into<ModelTemplate<C>> for Model<C> {
     fn into(self) -> ModelTemplate<C> {
         self.template.clone()
     }
 }
 
-/// This is a variation of a user [`Model`] generated by the [`crate::generator::Rules`]. One [`Model`] may be transformed into one ore more [`ModelVariation`] depending on the number of allowed rotations of the model.
+/// This is a variation of a user [`Model`] generated by the [`crate::generator::Rules`]. One [`Model`] may be transformed into one ore more [`ModelVariation`] depending on the number of allowed rotations and reflections of the model.
 #[derive(Debug)]
 pub struct ModelVariation {
     /// Allowed connections for this [`Model`] in the output
@@ -380,6 +808,10 @@ pub struct ModelVariation {
     original_index: ModelIndex,
     /// Rotation of the [`Model`]
     rotation: ModelRotation,
+    /// Reflection of the [`Model`], applied before the rotation
+    flip: ModelFlip,
+    /// Rotation of a hexagonal [`Model`]
+    hex_rotation: HexRotation,
 
     /// Debug name for this model
     #[cfg(feature = "models-names")]
@@ -403,11 +835,21 @@ impl ModelVariation {
     pub fn rotation(&self) -> ModelRotation {
         self.rotation
     }
+    /// Returns the reflection applied to the original [``Model`] this model was expanded from
+    pub fn flip(&self) -> ModelFlip {
+        self.flip
+    }
+    /// Returns the 60° rotation applied to the original hexagonal [``Model`] this model was expanded from
+    pub fn hex_rotation(&self) -> HexRotation {
+        self.hex_rotation
+    }
 
     pub(crate) fn to_instance(&self) -> ModelInstance {
         ModelInstance {
             model_index: self.original_index,
             rotation: self.rotation,
+            flip: self.flip,
+            hex_rotation: self.hex_rotation,
         }
     }
 }
@@ -421,11 +863,22 @@ pub struct ModelInstance {
     pub model_index: ModelIndex,
     /// Rotation of the original [`Model`]
     pub rotation: ModelRotation,
+    /// Reflection of the original [`Model`], applied before the rotation
+    pub flip: ModelFlip,
+    /// Rotation of the original hexagonal [`Model`]. Always [`HexRotation::Rot0`] in other coordinate systems.
+    pub hex_rotation: HexRotation,
 }
 
 impl fmt::Display for ModelInstance {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "id: {}, rot: {}", self.model_index, self.rotation)
+        write!(f, "id: {}, rot: {}", self.model_index, self.rotation)?;
+        if self.flip != ModelFlip::None {
+            write!(f, ", flip: {}", self.flip)?;
+        }
+        if self.hex_rotation != HexRotation::Rot0 {
+            write!(f, ", hex rot: {}", self.hex_rotation)?;
+        }
+        Ok(())
     }
 }
 
@@ -520,6 +973,21 @@ impl ModelRotation {
     pub fn next(&self) -> ModelRotation {
         self.rotated(ModelRotation::Rot90)
     }
+
+    #[inline]
+    /// Returns the inverse [`ModelRotation`]: the rotation that cancels this rotation.
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::generator::model::ModelRotation;
+    ///
+    /// let rot_90 = ModelRotation::Rot90;
+    /// assert_eq!(rot_90.inverse(), ModelRotation::Rot270);
+    /// ```
+    pub fn inverse(&self) -> ModelRotation {
+        ALL_MODEL_ROTATIONS
+            [(ALL_MODEL_ROTATIONS.len() - self.index() as usize) % ALL_MODEL_ROTATIONS.len()]
+    }
 }
 
 /// All the possible rotations for a [`Model`]
@@ -529,3 +997,153 @@ pub const ALL_MODEL_ROTATIONS: &'static [ModelRotation] = &[
     ModelRotation::Rot180,
     ModelRotation::Rot270,
 ];
+
+/// Represents a rotation of a hexagonal model, in the trigonometric(counterclockwise) direction
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub enum HexRotation {
+    /// Rotation of 0°
+    #[default]
+    Rot0,
+    /// Rotation of 60°
+    Rot60,
+    /// Rotation of 120°
+    Rot120,
+    /// Rotation of 180°
+    Rot180,
+    /// Rotation of 240°
+    Rot240,
+    /// Rotation of 300°
+    Rot300,
+}
+
+impl fmt::Display for HexRotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+impl HexRotation {
+    /// Returns the value of the rotation in °(degrees).
+    pub fn value(&self) -> u32 {
+        60 * self.index() as u32
+    }
+    /// Returns the value of the rotation in radians.
+    pub fn rad(&self) -> f32 {
+        f32::to_radians(self.value() as f32)
+    }
+
+    /// Returns the index of the enum member in the enumeration.
+    pub fn index(&self) -> u8 {
+        match *self {
+            HexRotation::Rot0 => 0,
+            HexRotation::Rot60 => 1,
+            HexRotation::Rot120 => 2,
+            HexRotation::Rot180 => 3,
+            HexRotation::Rot240 => 4,
+            HexRotation::Rot300 => 5,
+        }
+    }
+
+    #[inline]
+    /// Returns a new [`HexRotation`] equal to this rotation rotated by `rotation` counter-clock
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::generator::model::HexRotation;
+    ///
+    /// let rot_120 = HexRotation::Rot120;
+    /// assert_eq!(rot_120.rotated(HexRotation::Rot300), HexRotation::Rot60);
+    /// ```
+    pub fn rotated(&self, rotation: HexRotation) -> HexRotation {
+        ALL_HEX_ROTATIONS
+            [(self.index() as usize + rotation.index() as usize) % ALL_HEX_ROTATIONS.len()]
+    }
+
+    #[inline]
+    /// Returns the next [`HexRotation`]: this rotation rotated by 60° counter-clockwise.
+    pub fn next(&self) -> HexRotation {
+        self.rotated(HexRotation::Rot60)
+    }
+}
+
+/// All the possible rotations for a hexagonal [`Model`]
+pub const ALL_HEX_ROTATIONS: &[HexRotation] = &[
+    HexRotation::Rot0,
+    HexRotation::Rot60,
+    HexRotation::Rot120,
+    HexRotation::Rot180,
+    HexRotation::Rot240,
+    HexRotation::Rot300,
+];
+
+/// Represents a reflection in the plane orthogonal to a rotation axis.
+///
+/// Axes are taken from the rotation basis of the rotation axis: with a [`Direction::ZForward`] rotation axis (always the case in 2d), [`ModelFlip::X`] mirrors the X axis and [`ModelFlip::Y`] mirrors the Y axis.
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub enum ModelFlip {
+    /// No reflection
+    #[default]
+    None,
+    /// Mirrored along the first axis of the rotation plane
+    X,
+    /// Mirrored along the second axis of the rotation plane
+    Y,
+    /// Mirrored along both axes of the rotation plane. Equivalent to a rotation of 180°
+    XY,
+}
+
+impl fmt::Display for ModelFlip {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl ModelFlip {
+    /// Returns `true` if this reflection mirrors the first axis of the rotation plane.
+    pub fn flips_x(&self) -> bool {
+        matches!(self, ModelFlip::X | ModelFlip::XY)
+    }
+
+    /// Returns `true` if this reflection mirrors the second axis of the rotation plane.
+    pub fn flips_y(&self) -> bool {
+        matches!(self, ModelFlip::Y | ModelFlip::XY)
+    }
+
+    /// Returns an equivalent `(flip, rotation)` pair to this reflection followed by `rotation`, where the reflection is only [`ModelFlip::None`] or [`ModelFlip::X`].
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::generator::model::{ModelFlip, ModelRotation};
+    ///
+    /// assert_eq!(
+    ///     ModelFlip::XY.canonical(ModelRotation::Rot90),
+    ///     (ModelFlip::None, ModelRotation::Rot270)
+    /// );
+    /// ```
+    pub fn canonical(&self, rotation: ModelRotation) -> (ModelFlip, ModelRotation) {
+        match *self {
+            ModelFlip::None => (ModelFlip::None, rotation),
+            ModelFlip::X => (ModelFlip::X, rotation),
+            ModelFlip::Y => (ModelFlip::X, rotation.rotated(ModelRotation::Rot180)),
+            ModelFlip::XY => (ModelFlip::None, rotation.rotated(ModelRotation::Rot180)),
+        }
+    }
+
+    /// Returns the index of the enum member in the enumeration.
+    pub fn index(&self) -> u8 {
+        match *self {
+            ModelFlip::None => 0,
+            ModelFlip::X => 1,
+            ModelFlip::Y => 2,
+            ModelFlip::XY => 3,
+        }
+    }
+}
+
+/// All the possible reflections for a [`Model`]
+pub const ALL_MODEL_FLIPS: &[ModelFlip] =
+    &[ModelFlip::None, ModelFlip::X, ModelFlip::Y, ModelFlip::XY];
@@ -1,21 +1,167 @@
-use super::{model::ModelInstance, GeneratedNode, Generator};
+use std::sync::{Arc, Mutex};
 
-use crate::grid::{
-    coordinate_system::CoordinateSystem,
-    grid::{Grid, GridData},
+use super::{
+    model::{ModelInstance, ModelVariantIndex},
+    GeneratedNode, Generator,
+};
+
+use crate::{
+    grid::{
+        coordinate_system::CoordinateSystem,
+        grid::{Grid, GridData},
+    },
+    NodeIndex,
 };
 #[cfg(feature = "bevy")]
 use bevy::ecs::component::Component;
 
 /// Update sent by a [`crate::generator::Generator`]
+///
+/// `Generated`, `Reinitializing` and `Failed` are sent to all the observers. The other updates are only sent to the observers that subscribed to them, see [`DetailedUpdates`].
+///
+/// New kinds of updates may be added, matches on a [`GenerationUpdate`] need a wildcard arm.
 #[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
 pub enum GenerationUpdate {
     /// A node has been generated
     Generated(GeneratedNode),
     /// The generator is being reinitialized to its initial state, with a new seed.
+    ///
+    /// Also sent with the current seed when nodes are unset or when a region is regenerated, before the fixed nodes are generated again.
     Reinitializing(u64),
     /// The generation failed due to a contradiction at the specified node_index
     Failed(usize),
+    /// A model variant is not possible anymore on a node. Requires [`DetailedUpdates::bans`].
+    Banned {
+        /// Index of the node
+        node_index: NodeIndex,
+        /// Index of the banned model variant in the [`crate::generator::rules::Rules`]
+        model_variant_index: ModelVariantIndex,
+    },
+    /// The number of possible model variants on a node changed. Requires [`DetailedUpdates::possibilities_counts`].
+    ///
+    /// On a reinitialization, all the nodes go back to the total number of model variants without any update.
+    PossibilitiesCountChanged {
+        /// Index of the node
+        node_index: NodeIndex,
+        /// Number of model variants still possible on the node
+        count: usize,
+    },
+    /// The generator started to propagate the consequences of a node selection or of bans. Requires [`DetailedUpdates::propagation`].
+    PropagationStarted,
+    /// The generator finished to propagate, or stopped on a contradiction. Requires [`DetailedUpdates::propagation`].
+    PropagationEnded,
+    /// The generator entered a new [`GenerationPhase`]. Requires [`DetailedUpdates::phases`].
+    ///
+    /// A generator starts in [`GenerationPhase::Pregen`].
+    PhaseChanged(GenerationPhase),
+}
+
+/// Phases of a generation, see [`GenerationUpdate::PhaseChanged`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GenerationPhase {
+    /// The generator initializes its constraints and sets the initial nodes (or the fixed nodes when unsetting nodes or regenerating a region).
+    #[default]
+    Pregen,
+    /// The generator selects and generates the remaining nodes.
+    Generation,
+}
+
+/// Opt-in [`GenerationUpdate`] kinds that an observer can subscribe to, on top of the updates sent to all observers.
+///
+/// Those updates are numerous (a generation can ban millions of models) and are only computed when at least one observer subscribed to them.
+///
+/// ### Example
+///
+/// ```
+/// use procedural_tilemaps_core::generator::observer::DetailedUpdates;
+///
+/// let updates = DetailedUpdates::NONE.with_possibilities_counts().with_propagation();
+/// assert!(updates.propagation && !updates.bans);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DetailedUpdates {
+    /// Subscribes to [`GenerationUpdate::Banned`]
+    pub bans: bool,
+    /// Subscribes to [`GenerationUpdate::PossibilitiesCountChanged`]
+    pub possibilities_counts: bool,
+    /// Subscribes to [`GenerationUpdate::PropagationStarted`] and [`GenerationUpdate::PropagationEnded`]
+    pub propagation: bool,
+    /// Subscribes to [`GenerationUpdate::PhaseChanged`]
+    pub phases: bool,
+}
+
+impl DetailedUpdates {
+    /// No detailed updates
+    pub const NONE: DetailedUpdates = DetailedUpdates {
+        bans: false,
+        possibilities_counts: false,
+        propagation: false,
+        phases: false,
+    };
+    /// All the detailed updates
+    pub const ALL: DetailedUpdates = DetailedUpdates {
+        bans: true,
+        possibilities_counts: true,
+        propagation: true,
+        phases: true,
+    };
+
+    /// Subscribes to [`GenerationUpdate::Banned`]
+    pub fn with_bans(mut self) -> Self {
+        self.bans = true;
+        self
+    }
+
+    /// Subscribes to [`GenerationUpdate::PossibilitiesCountChanged`]
+    pub fn with_possibilities_counts(mut self) -> Self {
+        self.possibilities_counts = true;
+        self
+    }
+
+    /// Subscribes to [`GenerationUpdate::PropagationStarted`] and [`GenerationUpdate::PropagationEnded`]
+    pub fn with_propagation(mut self) -> Self {
+        self.propagation = true;
+        self
+    }
+
+    /// Subscribes to [`GenerationUpdate::PhaseChanged`]
+    pub fn with_phases(mut self) -> Self {
+        self.phases = true;
+        self
+    }
+
+    pub(crate) fn union(&self, other: &DetailedUpdates) -> DetailedUpdates {
+        DetailedUpdates {
+            bans: self.bans || other.bans,
+            possibilities_counts: self.possibilities_counts || other.possibilities_counts,
+            propagation: self.propagation || other.propagation,
+            phases: self.phases || other.phases,
+        }
+    }
+}
+
+/// Sending end of an observer queue
+#[derive(Clone)]
+pub(crate) struct ObserverQueue {
+    pub(crate) sender: crossbeam_channel::Sender<GenerationUpdate>,
+    pub(crate) detailed_updates: DetailedUpdates,
+}
+
+impl ObserverQueue {
+    pub(crate) fn new(
+        detailed_updates: DetailedUpdates,
+    ) -> (Self, crossbeam_channel::Receiver<GenerationUpdate>) {
+        // We can't simply bound to the number of nodes since we might retry some generations. (and send more than number_of_nodes updates)
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        (
+            Self {
+                sender,
+                detailed_updates,
+            },
+            receiver,
+        )
+    }
 }
 
 /// Observer with a queue of the [`GenerationUpdate`] sent by the [`crate::generator::Generator`] which also maintains a coherent state of the current generation in a [`GridData`]
@@ -30,7 +176,15 @@ pub struct QueuedStatefulObserver<T: CoordinateSystem, G: Grid<T>> {
 impl<T: CoordinateSystem, G: Grid<T>> QueuedStatefulObserver<T, G> {
     /// Creates a new [`QueuedStatefulObserver`] for a given [`crate::generator::Generator`]
     pub fn new(generator: &mut Generator<T, G>) -> Self {
-        let receiver = generator.create_observer_queue();
+        Self::with_detailed_updates(generator, DetailedUpdates::NONE)
+    }
+
+    /// Creates a new [`QueuedStatefulObserver`] for a given [`crate::generator::Generator`], which also receives the given [`DetailedUpdates`]. They do not modify the observer's state but are still returned by [`QueuedStatefulObserver::dequeue_one`].
+    pub fn with_detailed_updates(
+        generator: &mut Generator<T, G>,
+        detailed_updates: DetailedUpdates,
+    ) -> Self {
+        let receiver = generator.create_observer_queue(detailed_updates);
         QueuedStatefulObserver::create(receiver, generator.grid())
     }
 
@@ -58,6 +212,7 @@ impl<T: CoordinateSystem, G: Grid<T>> QueuedStatefulObserver<T, G> {
                     .set(grid_node.node_index, Some(grid_node.model_instance)),
                 GenerationUpdate::Reinitializing(_) => self.grid_data.reset(None),
                 GenerationUpdate::Failed(_) => self.grid_data.reset(None),
+                _ => (),
             }
         }
     }
@@ -74,6 +229,7 @@ impl<T: CoordinateSystem, G: Grid<T>> QueuedStatefulObserver<T, G> {
                         .set(grid_node.node_index, Some(grid_node.model_instance)),
                     GenerationUpdate::Reinitializing(_) => self.grid_data.reset(None),
                     GenerationUpdate::Failed(_) => self.grid_data.reset(None),
+                    _ => (),
                 }
                 Some(update)
             }
@@ -93,7 +249,15 @@ pub struct QueuedObserver {
 impl QueuedObserver {
     /// Creates a new [`QueuedObserver`] for a given [`crate::generator::Generator`]
     pub fn new<T: CoordinateSystem, G: Grid<T>>(generator: &mut Generator<T, G>) -> Self {
-        let receiver = generator.create_observer_queue();
+        Self::with_detailed_updates(generator, DetailedUpdates::NONE)
+    }
+
+    /// Creates a new [`QueuedObserver`] for a given [`crate::generator::Generator`], which also receives the given [`DetailedUpdates`].
+    pub fn with_detailed_updates<T: CoordinateSystem, G: Grid<T>>(
+        generator: &mut Generator<T, G>,
+        detailed_updates: DetailedUpdates,
+    ) -> Self {
+        let receiver = generator.create_observer_queue(detailed_updates);
         QueuedObserver { receiver }
     }
 
@@ -123,3 +287,71 @@ impl QueuedObserver {
         }
     }
 }
+
+/// Listener called synchronously by a [`crate::generator::Generator`] on generation events, without any channel or allocation. All the methods do nothing by default.
+///
+/// Listeners are registered as an `Arc<Mutex<_>>` with [`crate::generator::builder::GeneratorBuilder::with_listener`] or [`crate::generator::Generator::add_listener`], so that their state can still be accessed after (or during) the generation. A listener whose mutex was poisoned by a panic is still called.
+///
+/// ### Example
+///
+/// Counting the generated nodes and the contradictions of a generation
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use procedural_tilemaps_core::prelude::*;
+/// use procedural_tilemaps_core::{generator::{observer::GenerationListener, GeneratedNode}, NodeIndex};
+///
+/// #[derive(Default)]
+/// struct Stats {
+///     generated: u32,
+///     contradictions: u32,
+/// }
+///
+/// impl GenerationListener for Stats {
+///     fn on_generated(&mut self, _generated_node: GeneratedNode) {
+///         self.generated += 1;
+///     }
+///     fn on_contradiction(&mut self, _node_index: NodeIndex) {
+///         self.contradictions += 1;
+///     }
+/// }
+///
+/// let mut sockets = SocketCollection::new();
+/// let a = sockets.create();
+/// sockets.add_connection(a, vec![a]);
+/// let mut models = ModelCollection::<Cartesian2D>::new();
+/// models.create(SocketsCartesian2D::Mono(a));
+/// models.create(SocketsCartesian2D::Mono(a));
+/// let rules = RulesBuilder::new_cartesian_2d(models, sockets).build().unwrap();
+///
+/// let stats = Arc::new(Mutex::new(Stats::default()));
+/// let mut generator = GeneratorBuilder::new()
+///     .with_rules(rules)
+///     .with_grid(CartesianGrid::new_cartesian_2d(10, 10, false, false))
+///     .with_listener(stats.clone())
+///     .build()
+///     .unwrap();
+/// generator.generate().unwrap();
+///
+/// assert_eq!(stats.lock().unwrap().generated, 100);
+/// assert_eq!(stats.lock().unwrap().contradictions, 0);
+/// ```
+pub trait GenerationListener {
+    /// Called when a node is generated
+    fn on_generated(&mut self, _generated_node: GeneratedNode) {}
+    /// Called when a model variant is not possible anymore on a node
+    fn on_ban(&mut self, _node_index: NodeIndex, _model_variant_index: ModelVariantIndex) {}
+    /// Called when the generator is reinitialized with `seed`, see [`GenerationUpdate::Reinitializing`]
+    fn on_reinitialize(&mut self, _seed: u64) {}
+    /// Called when the generation fails due to a contradiction at `node_index`
+    fn on_contradiction(&mut self, _node_index: NodeIndex) {}
+}
+
+/// [`GenerationListener`] shared with a [`crate::generator::Generator`]
+pub(crate) type SharedListener = Arc<Mutex<dyn GenerationListener + Send>>;
+
+/// Observers and listeners registered on a [`crate::generator::builder::GeneratorBuilder`], given to the [`crate::generator::Generator`] it builds
+#[derive(Clone, Default)]
+pub(crate) struct GenerationNotifiers {
+    pub(crate) observers: Vec<ObserverQueue>,
+    pub(crate) listeners: Vec<SharedListener>,
+}
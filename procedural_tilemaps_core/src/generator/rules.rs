@@ -8,8 +8,11 @@ use crate::grid::{
     cartesian::coordinates::{Cartesian2D, Cartesian3D},
     coordinate_system::CoordinateSystem,
     direction::{Direction, DirectionTrait},
+    graph::coordinates::{GraphCoordinates, GraphDirection},
+    grid::{Grid, GridData},
+    hexagonal::coordinates::{HexDirection, Hexagonal},
 };
-use ndarray::{Array, Ix1, Ix2};
+use ndarray::{Array, Ix1, Ix2, Ix3};
 
 #[cfg(feature = "models-names")]
 use std::borrow::Cow;
@@ -24,8 +27,8 @@ use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
 
 use super::{
     model::{
-        Model, ModelCollection, ModelIndex, ModelInstance, ModelRotation, ModelVariantIndex,
-        ALL_MODEL_ROTATIONS,
+        HexRotation, Model, ModelCollection, ModelFlip, ModelIndex, ModelInstance, ModelMetadata,
+        ModelRotation, ModelVariantIndex, ALL_HEX_ROTATIONS, ALL_MODEL_FLIPS, ALL_MODEL_ROTATIONS,
     },
     socket::SocketCollection,
 };
@@ -135,6 +138,82 @@ impl RulesBuilder<Cartesian3D> {
     }
 }
 
+impl RulesBuilder<Hexagonal> {
+    /// Used to create Rules for a hexagonal grid.
+    ///
+    /// Hexagonal models are rotated by steps of 60° (see [`HexRotation`], [`super::model::Model::<Hexagonal>::with_hex_rotations`] and [`super::model::Model::with_all_rotations`]), and carry their rotation in [`ModelInstance::hex_rotation`]. They are never reflected: [`RulesBuilder::build`] fails if a model allows flips, or some 90° rotations.
+    ///
+    /// ### Example
+    ///
+    /// Create simple `Rules` where water tiles can only be surrounded by sand
+    /// ```
+    /// use procedural_tilemaps_core::generator::{socket::{SocketsHexagonal, SocketCollection}, rules::RulesBuilder, model::ModelCollection};
+    /// use procedural_tilemaps_core::grid::hexagonal::coordinates::Hexagonal;
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let (water, sand) = (sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(water, vec![sand]), (sand, vec![sand])]);
+    ///
+    /// let mut models = ModelCollection::<Hexagonal>::new();
+    /// models.create(SocketsHexagonal::Mono(water));
+    /// models.create(SocketsHexagonal::Mono(sand));
+    ///
+    /// let rules = RulesBuilder::new_hexagonal(models, sockets).build().unwrap();
+    /// ```
+    pub fn new_hexagonal(
+        models: ModelCollection<Hexagonal>,
+        socket_collection: SocketCollection,
+    ) -> Self {
+        Self {
+            models,
+            socket_collection,
+            // There is only one rotation axis on a hexagonal grid, this is only used as a placeholder.
+            rotation_axis: HexDirection::East,
+            coord_system: Hexagonal,
+        }
+    }
+}
+
+impl RulesBuilder<GraphCoordinates> {
+    /// Used to create Rules for a [`crate::grid::graph::GraphGrid`] using the direction slots of `coord_system`.
+    ///
+    /// Models on a graph are never rotated nor reflected by the rules.
+    ///
+    /// ### Example
+    ///
+    /// Rooms where a treasure room can only be reached from a corridor
+    /// ```
+    /// use procedural_tilemaps_core::generator::{socket::{SocketsGraph, SocketCollection}, rules::RulesBuilder, model::ModelCollection};
+    /// use procedural_tilemaps_core::grid::graph::{GraphCoordinates, GraphDirection};
+    ///
+    /// const LINK: GraphDirection = GraphDirection::new(0, 0);
+    /// let coord_system = GraphCoordinates::new(&[LINK]);
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let (corridor, treasure) = (sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(corridor, vec![corridor, treasure])]);
+    ///
+    /// let mut models = ModelCollection::<GraphCoordinates>::new();
+    /// models.create(SocketsGraph::mono(corridor, &coord_system));
+    /// models.create(SocketsGraph::mono(treasure, &coord_system));
+    ///
+    /// let rules = RulesBuilder::new_graph(models, sockets, coord_system).build().unwrap();
+    /// ```
+    pub fn new_graph(
+        models: ModelCollection<GraphCoordinates>,
+        socket_collection: SocketCollection,
+        coord_system: GraphCoordinates,
+    ) -> Self {
+        Self {
+            models,
+            socket_collection,
+            // There is no rotation axis on a graph, this is only used as a placeholder.
+            rotation_axis: GraphDirection::default(),
+            coord_system,
+        }
+    }
+}
+
 impl RulesBuilder<Cartesian3D> {
     /// Sets the [`Direction`] to be used in the [`Rules`] as the rotation axis for the models
     pub fn with_rotation_axis(mut self, rotation_axis: Direction) -> RulesBuilder<Cartesian3D> {
@@ -146,7 +225,25 @@ impl RulesBuilder<Cartesian3D> {
 impl<C: CoordinateSystem> RulesBuilder<C> {
     /// Builds the [`Rules`] from the current configuration of the [`RulesBuilder`]
     ///
-    /// May return [`crate::RulesBuilderError::NoModelsOrSockets`] if `models` or `socket_collection` are empty.
+    /// May return [`crate::RulesBuilderError::NoModelsOrSockets`] if `models` or `socket_collection` are empty, or [`crate::RulesBuilderError::UnsupportedTransformations`] if a model allows rotations or flips that the coordinate system cannot apply (reflections or 90° rotations of hexagonal models, any transformation on a graph).
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::{generator::{socket::{SocketsGraph, SocketCollection}, rules::RulesBuilder, model::ModelCollection}, RulesBuilderError};
+    /// use procedural_tilemaps_core::grid::graph::{GraphCoordinates, GraphDirection};
+    ///
+    /// const LINK: GraphDirection = GraphDirection::new(0, 0);
+    /// let coord_system = GraphCoordinates::new(&[LINK]);
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let a = sockets.create();
+    /// sockets.add_connection(a, vec![a]);
+    /// let mut models = ModelCollection::new();
+    /// models.create(SocketsGraph::mono(a, &coord_system)).with_all_rotations();
+    ///
+    /// let result = RulesBuilder::new_graph(models, sockets, coord_system).build();
+    /// assert!(matches!(result, Err(RulesBuilderError::UnsupportedTransformations(0))));
+    /// ```
     pub fn build(self) -> Result<Rules<C>, RulesBuilderError> {
         Rules::new(
             self.models,
@@ -165,6 +262,9 @@ pub struct ModelInfo {
     /// Weight of the original [`Model`]
     pub weight: f32,
 
+    /// Tags and metadata of the original [`Model`]
+    pub metadata: ModelMetadata,
+
     /// Name given to the original [`Model`]
     #[cfg(feature = "models-names")]
     pub name: Cow<'static, str>,
@@ -188,16 +288,20 @@ impl fmt::Display for ModelInfo {
 pub struct Rules<C: CoordinateSystem> {
     /// Number of original input models used to build these rules.
     original_models_count: usize,
-    /// Maps a [`super::model::ModelIndex`] and a [`super::model::ModelRotation`] to an optionnal corresponding [`ModelVariantIndex`]
-    models_mapping: Array<Option<ModelVariantIndex>, Ix2>,
+    /// Maps a [`super::model::ModelIndex`], a [`super::model::ModelRotation`] and a [`super::model::ModelFlip`] to an optionnal corresponding [`ModelVariantIndex`]
+    models_mapping: Array<Option<ModelVariantIndex>, Ix3>,
+    /// Maps a [`super::model::ModelIndex`] and a [`HexRotation`] to an optionnal corresponding [`ModelVariantIndex`]. Only filled with the rotated variants of hexagonal models.
+    hex_models_mapping: Array<Option<ModelVariantIndex>, Ix2>,
 
     /// All the model variations in this ruleset.
     ///
-    /// This is expanded from a given collection of base models, with added variations of rotations around an axis.
+    /// This is expanded from a given collection of base models, with added variations of rotations around an axis and reflections.
     models: Vec<ModelInstance>,
     weights: Vec<f32>,
     #[cfg(feature = "models-names")]
     names: Vec<Option<Cow<'static, str>>>,
+    /// Tags and metadata of the original models, indexed by [`ModelIndex`]
+    metadata: Vec<ModelMetadata>,
 
     /// The vector `allowed_neighbours[model_index][direction]` holds all the allowed adjacent models (indexes) to `model_index` in `direction`.
     ///
@@ -206,6 +310,8 @@ pub struct Rules<C: CoordinateSystem> {
     /// Note: this cannot be a simple 3d array since the third dimension is different for each element.
     allowed_neighbours: Array<Vec<usize>, Ix2>,
 
+    /// Axis around which the models are rotated, and whose orthogonal plane contains their reflections
+    rotation_axis: C::Direction,
     typestate: PhantomData<C>,
 }
 
@@ -217,11 +323,24 @@ impl<C: CoordinateSystem> Rules<C> {
         coord_system: C,
     ) -> Result<Rules<C>, RulesBuilderError> {
         let original_models_count = models.models_count();
-        let mut model_variations = models.create_variations(rotation_axis);
+        let metadata = models
+            .models()
+            .map(|model| model.metadata().clone())
+            .collect();
+        let mut model_variations = models.create_variations(rotation_axis, &socket_collection)?;
         // We test the expanded models because a model may have no rotations allowed.
         if model_variations.len() == 0 || socket_collection.is_empty() {
             return Err(RulesBuilderError::NoModelsOrSockets);
         }
+        for model in model_variations.iter() {
+            if model.sockets().len() != coord_system.directions_count() {
+                return Err(RulesBuilderError::InvalidSocketsCount(
+                    model.original_index(),
+                    model.sockets().len(),
+                    coord_system.directions_count(),
+                ));
+            }
+        }
 
         // Temporary collection to reverse the relation: sockets_to_models.get(socket)[direction] will hold all the models that have 'socket' from 'direction'
         let mut sockets_to_models = HashMap::new();
@@ -275,18 +394,43 @@ impl<C: CoordinateSystem> Rules<C> {
         #[cfg(feature = "models-names")]
         let mut names = Vec::with_capacity(model_variations.len());
 
-        let mut models_mapping =
-            Array::from_elem((original_models_count, ALL_MODEL_ROTATIONS.len()), None);
+        let mut models_mapping = Array::from_elem(
+            (
+                original_models_count,
+                ALL_MODEL_ROTATIONS.len(),
+                ALL_MODEL_FLIPS.len(),
+            ),
+            None,
+        );
+        let mut hex_models_mapping =
+            Array::from_elem((original_models_count, ALL_HEX_ROTATIONS.len()), None);
         for (index, model_variation) in model_variations.iter_mut().enumerate() {
             weights.push(model_variation.weight());
             model_instances.push(model_variation.to_instance());
             #[cfg(feature = "models-names")]
             names.push(model_variation.name.take());
 
-            models_mapping[(
-                model_variation.original_index(),
-                model_variation.rotation().index() as usize,
-            )] = Some(index);
+            if model_variation.hex_rotation() != HexRotation::Rot0 {
+                hex_models_mapping[(
+                    model_variation.original_index(),
+                    model_variation.hex_rotation().index() as usize,
+                )] = Some(index);
+                continue;
+            }
+
+            // A variation can be referenced by any equivalent combination of reflection & rotation
+            let transformation = model_variation.flip().canonical(model_variation.rotation());
+            for flip in ALL_MODEL_FLIPS {
+                for rot in ALL_MODEL_ROTATIONS {
+                    if flip.canonical(*rot) == transformation {
+                        models_mapping[(
+                            model_variation.original_index(),
+                            rot.index() as usize,
+                            flip.index() as usize,
+                        )] = Some(index);
+                    }
+                }
+            }
         }
 
         #[cfg(feature = "debug-traces")]
@@ -300,11 +444,14 @@ impl<C: CoordinateSystem> Rules<C> {
         Ok(Rules {
             original_models_count,
             models_mapping,
+            hex_models_mapping,
             models: model_instances,
             weights,
             #[cfg(feature = "models-names")]
             names,
+            metadata,
             allowed_neighbours,
+            rotation_axis,
             typestate: PhantomData,
         })
     }
@@ -330,6 +477,12 @@ impl<C: CoordinateSystem> Rules<C> {
         self.original_models_count
     }
 
+    /// Returns the axis around which the models of these rules are rotated. Their reflections are in the plane orthogonal to this axis, see [`ModelFlip`].
+    #[inline]
+    pub fn rotation_axis(&self) -> C::Direction {
+        self.rotation_axis
+    }
+
     #[inline]
     pub(crate) fn model(&self, index: ModelVariantIndex) -> &ModelInstance {
         &self.models[index]
@@ -338,6 +491,7 @@ impl<C: CoordinateSystem> Rules<C> {
     pub(crate) fn model_info(&self, model_index: ModelVariantIndex) -> ModelInfo {
         ModelInfo {
             weight: self.weights[model_index],
+            metadata: self.metadata[self.models[model_index].model_index].clone(),
 
             #[cfg(feature = "models-names")]
             name: self.name_unchecked(model_index),
@@ -362,19 +516,131 @@ impl<C: CoordinateSystem> Rules<C> {
         model_index < self.models.len()
     }
 
+    /// Returns the tags and metadata of the original model with index `model_index`. Returns [`None`] if this model index is not valid.
+    pub fn model_metadata(&self, model_index: ModelIndex) -> Option<&ModelMetadata> {
+        self.metadata.get(model_index)
+    }
+
+    /// Returns the tags and metadata of a model variant, shared with its original model. Returns [`None`] if this model variant index is not valid.
+    pub fn metadata(&self, model_index: ModelVariantIndex) -> Option<&ModelMetadata> {
+        self.models
+            .get(model_index)
+            .map(|model| &self.metadata[model.model_index])
+    }
+
+    /// Returns an iterator over the tags of a model variant. Empty if this model variant index is not valid.
+    pub fn tags(&self, model_index: ModelVariantIndex) -> impl Iterator<Item = &str> {
+        self.metadata(model_index)
+            .into_iter()
+            .flat_map(|metadata| metadata.tags())
+    }
+
+    /// Returns `true` if the model variant has the tag `tag`
+    pub fn has_tag(&self, model_index: ModelVariantIndex, tag: &str) -> bool {
+        self.metadata(model_index)
+            .is_some_and(|metadata| metadata.has_tag(tag))
+    }
+
+    /// Returns the metadata value of `key` for a model variant, if any
+    pub fn metadata_value(&self, model_index: ModelVariantIndex, key: &str) -> Option<&str> {
+        self.metadata(model_index)
+            .and_then(|metadata| metadata.get(key))
+    }
+
+    /// Returns the indexes of all the original models with the tag `tag`
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::prelude::*;
+    ///
+    /// let mut models = ModelCollection::<Cartesian2D>::new();
+    /// let mut sockets = SocketCollection::new();
+    /// let (grass, water) = (sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(grass, vec![grass, water]), (water, vec![water])]);
+    /// models.create(SocketsCartesian2D::Mono(grass)).with_tag("walkable");
+    /// models
+    ///     .create(SocketsCartesian2D::Mono(water))
+    ///     .with_tags(["water", "blocking"])
+    ///     .with_metadata("depth", "2");
+    ///
+    /// let rules = RulesBuilder::new_cartesian_2d(models, sockets).build().unwrap();
+    /// assert_eq!(rules.models_with_tag("water"), vec![1]);
+    /// assert_eq!(rules.tags(1).collect::<Vec<_>>(), vec!["blocking", "water"]);
+    /// assert!(rules.has_tag(0, "walkable"));
+    /// assert_eq!(rules.metadata_value(1, "depth"), Some("2"));
+    /// ```
+    pub fn models_with_tag(&self, tag: &str) -> Vec<ModelIndex> {
+        self.metadata
+            .iter()
+            .enumerate()
+            .filter(|(_, metadata)| metadata.has_tag(tag))
+            .map(|(model_index, _)| model_index)
+            .collect()
+    }
+
+    /// Returns the indexes of all the model variants with the tag `tag`
+    pub fn variants_with_tag(&self, tag: &str) -> Vec<ModelVariantIndex> {
+        self.models
+            .iter()
+            .enumerate()
+            .filter(|(_, model)| self.metadata[model.model_index].has_tag(tag))
+            .map(|(model_index, _)| model_index)
+            .collect()
+    }
+
     /// Returns `Some` [`ModelVariantIndex`] corresponding to the original model with index `model_index` rotated by `rot`. Returns [`None`] if this variation does not exist.
     pub fn variant_index(
         &self,
         model_index: ModelIndex,
         rot: ModelRotation,
+    ) -> Option<ModelVariantIndex> {
+        self.flipped_variant_index(model_index, rot, ModelFlip::None)
+    }
+
+    /// Returns `Some` [`ModelVariantIndex`] corresponding to the original model with index `model_index` reflected by `flip` then rotated by `rot`. Returns [`None`] if this variation does not exist.
+    pub fn flipped_variant_index(
+        &self,
+        model_index: ModelIndex,
+        rot: ModelRotation,
+        flip: ModelFlip,
     ) -> Option<ModelVariantIndex> {
         if model_index < self.original_models_count {
-            self.models_mapping[(model_index, rot.index() as usize)]
+            self.models_mapping[(model_index, rot.index() as usize, flip.index() as usize)]
         } else {
             None
         }
     }
 
+    /// Returns `Some` [`ModelVariantIndex`] corresponding to the original hexagonal model with index `model_index` rotated by `rot`. Returns [`None`] if this variation does not exist.
+    pub fn hex_variant_index(
+        &self,
+        model_index: ModelIndex,
+        rot: HexRotation,
+    ) -> Option<ModelVariantIndex> {
+        if model_index >= self.original_models_count {
+            None
+        } else if rot == HexRotation::Rot0 {
+            self.variant_index(model_index, ModelRotation::Rot0)
+        } else {
+            self.hex_models_mapping[(model_index, rot.index() as usize)]
+        }
+    }
+
+    /// Returns `Some` [`ModelVariantIndex`] corresponding to the model variation of `instance`. Returns [`None`] if this variation does not exist.
+    pub fn instance_variant_index(&self, instance: &ModelInstance) -> Option<ModelVariantIndex> {
+        match instance.hex_rotation {
+            HexRotation::Rot0 => {
+                self.flipped_variant_index(instance.model_index, instance.rotation, instance.flip)
+            }
+            hex_rotation
+                if (instance.rotation, instance.flip) == (ModelRotation::Rot0, ModelFlip::None) =>
+            {
+                self.hex_variant_index(instance.model_index, hex_rotation)
+            }
+            _ => None,
+        }
+    }
+
     #[cfg(feature = "models-names")]
     #[inline]
     pub(crate) fn name_unchecked(&self, model_index: ModelVariantIndex) -> Cow<'static, str> {
@@ -405,6 +671,62 @@ impl<C: CoordinateSystem> Rules<C> {
     }
 }
 
+impl<C: CoordinateSystem, G: Grid<C>> GridData<C, ModelInstance, G> {
+    /// Returns a [`GridData`] holding, for each node, whether its generated model has the tag `tag` in `rules`.
+    ///
+    /// `rules` should be the [`Rules`] that generated this grid. Nodes whose model is unknown in `rules` are `false`.
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::prelude::*;
+    /// use procedural_tilemaps_core::generator::model::ModelInstance;
+    ///
+    /// let mut models = ModelCollection::<Cartesian2D>::new();
+    /// let mut sockets = SocketCollection::new();
+    /// let (grass, water) = (sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(grass, vec![grass, water]), (water, vec![water])]);
+    /// models.create(SocketsCartesian2D::Mono(grass));
+    /// models
+    ///     .create(SocketsCartesian2D::Mono(water))
+    ///     .with_tag("water")
+    ///     .with_metadata("cost", "3");
+    /// let rules = RulesBuilder::new_cartesian_2d(models, sockets).build().unwrap();
+    ///
+    /// let grid = CartesianGrid::new_cartesian_2d(3, 1, false, false);
+    /// let mut grid_data = grid.new_grid_data(ModelInstance {
+    ///     model_index: 0,
+    ///     rotation: ModelRotation::Rot0,
+    ///     flip: ModelFlip::None,
+    ///     hex_rotation: HexRotation::Rot0,
+    /// });
+    /// grid_data.get_mut(1).model_index = 1;
+    ///
+    /// let water = grid_data.tag_mask(&rules, "water");
+    /// assert_eq!(water.iter().copied().collect::<Vec<_>>(), vec![false, true, false]);
+    /// let costs = grid_data.map_metadata(&rules, |metadata| metadata.get_parsed::<u32>("cost").unwrap_or(1));
+    /// assert_eq!(costs.iter().copied().collect::<Vec<_>>(), vec![1, 3, 1]);
+    /// ```
+    pub fn tag_mask(&self, rules: &Rules<C>, tag: &str) -> GridData<C, bool, G> {
+        self.map(|instance| {
+            rules
+                .model_metadata(instance.model_index)
+                .is_some_and(|metadata| metadata.has_tag(tag))
+        })
+    }
+
+    /// Returns a [`GridData`] holding, for each node, the result of `f` on the [`ModelMetadata`] of its generated model in `rules`.
+    ///
+    /// `rules` should be the [`Rules`] that generated this grid. Nodes whose model is unknown in `rules` are given an empty [`ModelMetadata`].
+    pub fn map_metadata<T>(
+        &self,
+        rules: &Rules<C>,
+        mut f: impl FnMut(&ModelMetadata) -> T,
+    ) -> GridData<C, T, G> {
+        let empty = ModelMetadata::default();
+        self.map(|instance| f(rules.model_metadata(instance.model_index).unwrap_or(&empty)))
+    }
+}
+
 /// Represents a reference to a [`super::model::ModelVariation`] of some [`Rules`]
 pub trait ModelVariantRef<C: CoordinateSystem> {
     /// Returns the [`ModelVariantIndex`] that is referenced by this `ModelVariantRef`.
@@ -419,18 +741,12 @@ impl<C: CoordinateSystem> ModelVariantRef<C> for ModelVariantIndex {
 }
 impl<C: CoordinateSystem> ModelVariantRef<C> for Model<C> {
     fn to_index(&self, rules: &Rules<C>) -> Result<ModelVariantIndex, NodeSetError> {
-        let rot = self.first_rot();
-        rules
-            .variant_index(self.index(), rot)
-            .ok_or(NodeSetError::InvalidModelRef(self.index(), rot))
+        self.instance().to_index(rules)
     }
 }
 impl<C: CoordinateSystem> ModelVariantRef<C> for &Model<C> {
     fn to_index(&self, rules: &Rules<C>) -> Result<ModelVariantIndex, NodeSetError> {
-        let rot = self.first_rot();
-        rules
-            .variant_index(self.index(), rot)
-            .ok_or(NodeSetError::InvalidModelRef(self.index(), rot))
+        self.instance().to_index(rules)
     }
 }
 impl<C: CoordinateSystem> ModelVariantRef<C> for (ModelIndex, ModelRotation) {
@@ -440,6 +756,20 @@ impl<C: CoordinateSystem> ModelVariantRef<C> for (ModelIndex, ModelRotation) {
             .ok_or(NodeSetError::InvalidModelRef(self.0, self.1))
     }
 }
+impl<C: CoordinateSystem> ModelVariantRef<C> for (ModelIndex, ModelRotation, ModelFlip) {
+    fn to_index(&self, rules: &Rules<C>) -> Result<ModelVariantIndex, NodeSetError> {
+        rules
+            .flipped_variant_index(self.0, self.1, self.2)
+            .ok_or(NodeSetError::InvalidModelRef(self.0, self.1))
+    }
+}
+impl<C: CoordinateSystem> ModelVariantRef<C> for (ModelIndex, HexRotation) {
+    fn to_index(&self, rules: &Rules<C>) -> Result<ModelVariantIndex, NodeSetError> {
+        rules
+            .hex_variant_index(self.0, self.1)
+            .ok_or(NodeSetError::InvalidModelRef(self.0, ModelRotation::Rot0))
+    }
+}
 impl<C: CoordinateSystem> ModelVariantRef<C> for (Model<C>, ModelRotation) {
     fn to_index(&self, rules: &Rules<C>) -> Result<ModelVariantIndex, NodeSetError> {
         rules
@@ -457,7 +787,7 @@ impl<C: CoordinateSystem> ModelVariantRef<C> for (&Model<C>, ModelRotation) {
 impl<C: CoordinateSystem> ModelVariantRef<C> for ModelInstance {
     fn to_index(&self, rules: &Rules<C>) -> Result<ModelVariantIndex, NodeSetError> {
         rules
-            .variant_index(self.model_index, self.rotation)
+            .instance_variant_index(self)
             .ok_or(NodeSetError::InvalidModelRef(
                 self.model_index,
                 self.rotation,
@@ -467,7 +797,7 @@ impl<C: CoordinateSystem> ModelVariantRef<C> for ModelInstance {
 impl<C: CoordinateSystem> ModelVariantRef<C> for &ModelInstance {
     fn to_index(&self, rules: &Rules<C>) -> Result<ModelVariantIndex, NodeSetError> {
         rules
-            .variant_index(self.model_index, self.rotation)
+            .instance_variant_index(self)
             .ok_or(NodeSetError::InvalidModelRef(
                 self.model_index,
                 self.rotation,
@@ -0,0 +1,392 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+
+use crate::{
+    grid::cartesian::coordinates::{Cartesian2D, Cartesian3D, CartesianCoordinates},
+    RulesDefinitionError,
+};
+
+use super::{
+    model::{Model, ModelCollection, ModelIndex, ALL_MODEL_ROTATIONS, DEFAULT_MODEL_WEIGHT},
+    rules::{Rules, RulesBuilder},
+    socket::{Socket, SocketCollection, SocketsCartesian2D, SocketsCartesian3D},
+};
+
+/// Serializable definition of some [`Rules`]: sockets referenced by name, their connections and the models.
+///
+/// The models are created in order, so the model at position `i` in `models` has the [`ModelIndex`] `i`.
+///
+/// The definition does not depend on a data format. The RON rules files of `bevy_procedural_tilemaps` are parsed with the `IMPLICIT_SOME` extension, which allows `Option` fields to be given without `Some(...)`.
+///
+/// ### Example
+/// ```
+/// use procedural_tilemaps_core::prelude::*;
+/// use procedural_tilemaps_core::generator::rules_definition::RulesDefinition;
+/// use procedural_tilemaps_core::RulesDefinitionError;
+///
+/// let definition: RulesDefinition = ron::from_str(
+///     r#"(
+///         sockets: ["grass", "water", "shore"],
+///         connections: [
+///             ("grass", ["grass", "shore"]),
+///             ("water", ["water", "shore"]),
+///         ],
+///         models: [
+///             (name: Some("grass"), sockets: Mono("grass"), weight: 2.0, tags: ["walkable"]),
+///             (name: Some("water"), sockets: Mono("water"), metadata: {"ascii": "~"}),
+///             (
+///                 name: Some("shore"),
+///                 sockets: Simple(x_pos: "shore", x_neg: "shore", y_pos: "grass", y_neg: "water"),
+///                 rotations: All,
+///             ),
+///         ],
+///     )"#,
+/// )
+/// .unwrap();
+///
+/// let rules = definition.rules::<Cartesian2D>().unwrap();
+/// assert_eq!(rules.original_models_count(), 3);
+/// assert_eq!(rules.models_count(), 6);
+/// assert_eq!(rules.models_with_tag("walkable"), vec![0]);
+///
+/// // Cartesian3D models need sockets for the z axis
+/// assert!(definition.rules::<Cartesian3D>().is_err());
+///
+/// // Socket names must be unique
+/// let mut duplicated = definition.clone();
+/// duplicated.sockets.push("water".to_string());
+/// assert!(matches!(
+///     duplicated.rules::<Cartesian2D>(),
+///     Err(RulesDefinitionError::DuplicateSocket(name)) if name == "water"
+/// ));
+/// ```
+#[derive(Deserialize, Clone, Debug)]
+pub struct RulesDefinition {
+    /// Names of all the sockets
+    pub sockets: Vec<String>,
+    /// Connections between sockets: each socket is connected to the sockets of its list
+    pub connections: Vec<(String, Vec<String>)>,
+    /// Connections between sockets on the rotation axis (only in 3d), see [`SocketCollection::add_rotated_connection`]
+    #[serde(default)]
+    pub rotated_connections: Vec<(String, Vec<String>)>,
+    /// Models definitions
+    pub models: Vec<ModelDefinition>,
+}
+
+/// Definition of a model in a [`RulesDefinition`]
+#[derive(Deserialize, Clone, Debug)]
+pub struct ModelDefinition {
+    /// Name of the model. Only registered if the `models-names` feature is enabled.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Sockets of the model
+    pub sockets: SocketsDefinition,
+    /// Weight of the model. Defaults to [`DEFAULT_MODEL_WEIGHT`].
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    /// Allowed rotations of the model
+    #[serde(default)]
+    pub rotations: RotationsDefinition,
+    /// Whether the mirrored variations of the model are allowed. Defaults to `false`.
+    #[serde(default)]
+    pub reflections: bool,
+    /// Tags of the model, see [`super::model::ModelMetadata`]
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Key/value metadata of the model, see [`super::model::ModelMetadata`]
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    /// Assets to spawn for this model. Not used by this crate, they are loaded by `bevy_procedural_tilemaps`.
+    #[serde(default)]
+    pub assets: Vec<ModelAssetDefinition>,
+}
+
+fn default_weight() -> f32 {
+    DEFAULT_MODEL_WEIGHT
+}
+
+/// Sockets of a [`ModelDefinition`], by socket name.
+///
+/// The `z_pos` and `z_neg` sockets must be given for [`Cartesian3D`] rules, and must not be given for [`Cartesian2D`] rules.
+#[derive(Deserialize, Clone, Debug)]
+pub enum SocketsDefinition {
+    /// The model has the same socket on all its sides
+    Mono(String),
+    /// The model has one socket per side
+    Simple {
+        /// Socket on the x+ side
+        x_pos: String,
+        /// Socket on the x- side
+        x_neg: String,
+        /// Socket on the y+ side
+        y_pos: String,
+        /// Socket on the y- side
+        y_neg: String,
+        /// Socket on the z+ side
+        #[serde(default)]
+        z_pos: Option<String>,
+        /// Socket on the z- side
+        #[serde(default)]
+        z_neg: Option<String>,
+    },
+    /// The model has multiple sockets per side
+    Multiple {
+        /// Sockets on the x+ side
+        x_pos: Vec<String>,
+        /// Sockets on the x- side
+        x_neg: Vec<String>,
+        /// Sockets on the y+ side
+        y_pos: Vec<String>,
+        /// Sockets on the y- side
+        y_neg: Vec<String>,
+        /// Sockets on the z+ side
+        #[serde(default)]
+        z_pos: Option<Vec<String>>,
+        /// Sockets on the z- side
+        #[serde(default)]
+        z_neg: Option<Vec<String>>,
+    },
+}
+
+/// Allowed rotations of a [`ModelDefinition`]
+#[derive(Deserialize, Clone, Debug, Default)]
+pub enum RotationsDefinition {
+    /// The model is not rotated
+    #[default]
+    None,
+    /// All the rotations of the model are allowed
+    All,
+    /// Only the listed rotations, in degrees (0, 90, 180 or 270), are allowed
+    Only(Vec<u32>),
+}
+
+/// Asset of a [`ModelDefinition`], referenced by path
+#[derive(Deserialize, Clone, Debug)]
+pub struct ModelAssetDefinition {
+    /// Path of the asset, relative to the assets folder
+    pub path: String,
+    /// Grid offset from the generated grid node position
+    #[serde(default)]
+    pub grid_offset: (i32, i32, i32),
+    /// World offset from the generated grid node position
+    #[serde(default)]
+    pub world_offset: (f32, f32, f32),
+}
+
+impl RulesDefinition {
+    /// Builds the [`Rules`] defined by this definition for the coordinate system `C`
+    pub fn rules<C: RulesDefinitionCoordinates>(&self) -> Result<Rules<C>, RulesDefinitionError> {
+        let mut sockets = SocketCollection::new();
+        let mut sockets_by_name = HashMap::new();
+        for name in self.sockets.iter() {
+            if sockets_by_name
+                .insert(name.as_str(), sockets.create())
+                .is_some()
+            {
+                return Err(RulesDefinitionError::DuplicateSocket(name.clone()));
+            }
+        }
+        let socket = |name: &String| {
+            sockets_by_name
+                .get(name.as_str())
+                .copied()
+                .ok_or_else(|| RulesDefinitionError::UnknownSocket(name.clone()))
+        };
+        let socket_list =
+            |names: &Vec<String>| names.iter().map(socket).collect::<Result<Vec<_>, _>>();
+
+        for (from, to) in self.connections.iter() {
+            sockets.add_connection(socket(from)?, socket_list(to)?);
+        }
+        for (from, to) in self.rotated_connections.iter() {
+            sockets.add_rotated_connection(socket(from)?, socket_list(to)?);
+        }
+
+        let mut models = ModelCollection::<C>::new();
+        for (model_index, definition) in self.models.iter().enumerate() {
+            let model_sockets = match &definition.sockets {
+                SocketsDefinition::Mono(name) => ModelSockets::Mono(socket(name)?),
+                SocketsDefinition::Simple {
+                    x_pos,
+                    x_neg,
+                    y_pos,
+                    y_neg,
+                    z_pos,
+                    z_neg,
+                } => ModelSockets::Sides {
+                    x_pos: vec![socket(x_pos)?],
+                    x_neg: vec![socket(x_neg)?],
+                    y_pos: vec![socket(y_pos)?],
+                    y_neg: vec![socket(y_neg)?],
+                    z: match (z_pos, z_neg) {
+                        (None, None) => None,
+                        (Some(z_pos), Some(z_neg)) => {
+                            Some((vec![socket(z_pos)?], vec![socket(z_neg)?]))
+                        }
+                        _ => return Err(RulesDefinitionError::MissingZSockets(model_index)),
+                    },
+                },
+                SocketsDefinition::Multiple {
+                    x_pos,
+                    x_neg,
+                    y_pos,
+                    y_neg,
+                    z_pos,
+                    z_neg,
+                } => ModelSockets::Sides {
+                    x_pos: socket_list(x_pos)?,
+                    x_neg: socket_list(x_neg)?,
+                    y_pos: socket_list(y_pos)?,
+                    y_neg: socket_list(y_neg)?,
+                    z: match (z_pos, z_neg) {
+                        (None, None) => None,
+                        (Some(z_pos), Some(z_neg)) => {
+                            Some((socket_list(z_pos)?, socket_list(z_neg)?))
+                        }
+                        _ => return Err(RulesDefinitionError::MissingZSockets(model_index)),
+                    },
+                },
+            };
+
+            let model = C::create_model(&mut models, model_index, model_sockets)?;
+            model.with_weight(definition.weight);
+            match &definition.rotations {
+                RotationsDefinition::None => (),
+                RotationsDefinition::All => {
+                    model.with_all_rotations();
+                }
+                RotationsDefinition::Only(degrees) => {
+                    let mut rotations = HashSet::new();
+                    for degrees in degrees.iter() {
+                        let rotation = ALL_MODEL_ROTATIONS
+                            .iter()
+                            .find(|rotation| rotation.value() == *degrees)
+                            .ok_or(RulesDefinitionError::InvalidRotation(model_index, *degrees))?;
+                        rotations.insert(*rotation);
+                    }
+                    model.with_rotations(rotations);
+                }
+            }
+            if definition.reflections {
+                model.with_reflections();
+            }
+            if let Some(name) = &definition.name {
+                model.with_name(name.clone());
+            }
+            model.with_tags(definition.tags.iter().cloned());
+            for (key, value) in definition.metadata.iter() {
+                model.with_metadata(key.clone(), value.clone());
+            }
+        }
+
+        Ok(C::rules_builder(models, sockets).build()?)
+    }
+}
+
+/// Sockets of a model, resolved from a [`SocketsDefinition`]
+pub enum ModelSockets {
+    /// Same socket on all the sides
+    Mono(Socket),
+    /// Sockets per side. `z` holds the `z_pos` and `z_neg` sockets, if any.
+    Sides {
+        /// Sockets on the x+ side
+        x_pos: Vec<Socket>,
+        /// Sockets on the x- side
+        x_neg: Vec<Socket>,
+        /// Sockets on the y+ side
+        y_pos: Vec<Socket>,
+        /// Sockets on the y- side
+        y_neg: Vec<Socket>,
+        /// Sockets on the z+ and z- sides
+        z: Option<(Vec<Socket>, Vec<Socket>)>,
+    },
+}
+
+/// Coordinate systems for which [`Rules`] can be built from a [`RulesDefinition`]
+pub trait RulesDefinitionCoordinates: CartesianCoordinates {
+    /// Creates a model with the given sockets in `models`
+    fn create_model(
+        models: &mut ModelCollection<Self>,
+        model_index: ModelIndex,
+        sockets: ModelSockets,
+    ) -> Result<&mut Model<Self>, RulesDefinitionError>;
+
+    /// Creates a [`RulesBuilder`] for this coordinate system
+    fn rules_builder(
+        models: ModelCollection<Self>,
+        sockets: SocketCollection,
+    ) -> RulesBuilder<Self>;
+}
+
+impl RulesDefinitionCoordinates for Cartesian2D {
+    fn create_model(
+        models: &mut ModelCollection<Self>,
+        model_index: ModelIndex,
+        sockets: ModelSockets,
+    ) -> Result<&mut Model<Self>, RulesDefinitionError> {
+        let sockets = match sockets {
+            ModelSockets::Mono(socket) => SocketsCartesian2D::Mono(socket),
+            ModelSockets::Sides {
+                x_pos,
+                x_neg,
+                y_pos,
+                y_neg,
+                z: None,
+            } => SocketsCartesian2D::Multiple {
+                x_pos,
+                x_neg,
+                y_pos,
+                y_neg,
+            },
+            ModelSockets::Sides { z: Some(_), .. } => {
+                return Err(RulesDefinitionError::UnexpectedZSockets(model_index))
+            }
+        };
+        Ok(models.create(sockets))
+    }
+
+    fn rules_builder(
+        models: ModelCollection<Self>,
+        sockets: SocketCollection,
+    ) -> RulesBuilder<Self> {
+        RulesBuilder::new_cartesian_2d(models, sockets)
+    }
+}
+
+impl RulesDefinitionCoordinates for Cartesian3D {
+    fn create_model(
+        models: &mut ModelCollection<Self>,
+        model_index: ModelIndex,
+        sockets: ModelSockets,
+    ) -> Result<&mut Model<Self>, RulesDefinitionError> {
+        let sockets = match sockets {
+            ModelSockets::Mono(socket) => SocketsCartesian3D::Mono(socket),
+            ModelSockets::Sides {
+                x_pos,
+                x_neg,
+                y_pos,
+                y_neg,
+                z: Some((z_pos, z_neg)),
+            } => SocketsCartesian3D::Multiple {
+                x_pos,
+                x_neg,
+                z_pos,
+                z_neg,
+                y_pos,
+                y_neg,
+            },
+            ModelSockets::Sides { z: None, .. } => {
+                return Err(RulesDefinitionError::MissingZSockets(model_index))
+            }
+        };
+        Ok(models.create(sockets))
+    }
+
+    fn rules_builder(
+        models: ModelCollection<Self>,
+        sockets: SocketCollection,
+    ) -> RulesBuilder<Self> {
+        RulesBuilder::new_cartesian_3d(models, sockets)
+    }
+}
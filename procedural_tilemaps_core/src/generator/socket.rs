@@ -1,6 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::grid::cartesian::coordinates::{Cartesian2D, Cartesian3D};
+use crate::grid::{
+    cartesian::coordinates::{Cartesian2D, Cartesian3D},
+    coordinate_system::CoordinateSystem,
+    graph::coordinates::GraphCoordinates,
+    hexagonal::coordinates::Hexagonal,
+};
 
 use super::model::{ModelRotation, ModelTemplate, ALL_MODEL_ROTATIONS};
 
@@ -17,6 +22,8 @@ pub struct SocketCollection {
     uniques: HashMap<SocketId, HashSet<SocketId>>,
     /// For determinism and sequential access
     compatibles: HashMap<SocketId, Vec<SocketId>>,
+    /// Maps a socket index to the index of its mirror image. Sockets not in this map are their own mirror image.
+    mirrors: HashMap<u32, u32>,
 }
 
 impl SocketCollection {
@@ -26,6 +33,7 @@ impl SocketCollection {
             incremental_socket_index: 0,
             uniques: HashMap::new(),
             compatibles: HashMap::new(),
+            mirrors: HashMap::new(),
         }
     }
 
@@ -183,6 +191,42 @@ impl SocketCollection {
         self
     }
 
+    /// Declares that `a` and `b` are chiral sockets: each one is the mirror image of the other.
+    ///
+    /// When a [`super::model::Model`] is reflected (see [`ModelTemplate::with_reflections`]), the order in which each
+    /// of its sides is read is reversed. An asymmetric socket such as a "grass then void" edge then becomes a "void
+    /// then grass" edge. Sockets that are not declared in a chiral pair are considered symmetric and stay the same
+    /// when mirrored.
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::generator::socket::SocketCollection;
+    ///
+    /// let mut sockets = SocketCollection::new();
+    /// let (grass_and_void, void_and_grass) = (sockets.create(), sockets.create());
+    /// sockets.add_connection(grass_and_void, vec![void_and_grass]);
+    /// // A mirrored `grass_and_void` edge is a `void_and_grass` edge
+    /// sockets.add_chiral_pair(grass_and_void, void_and_grass);
+    /// ```
+    pub fn add_chiral_pair(&mut self, a: Socket, b: Socket) -> &mut Self {
+        self.mirrors.insert(a.socket_index, b.socket_index);
+        self.mirrors.insert(b.socket_index, a.socket_index);
+        self
+    }
+
+    /// Returns the mirror image of `socket`.
+    ///
+    /// Sockets on the rotation axis also have their rotation mirrored.
+    pub(crate) fn mirrored(&self, socket: &Socket) -> Socket {
+        Socket {
+            socket_index: *self
+                .mirrors
+                .get(&socket.socket_index)
+                .unwrap_or(&socket.socket_index),
+            rot: socket.rot.inverse(),
+        }
+    }
+
     fn register_connection_half(&mut self, from: &Socket, to: &Socket) {
         // TODO Decide if we check for existence
         let connectable_sockets = self.uniques.entry(from.id()).or_insert(HashSet::new());
@@ -393,3 +437,122 @@ impl SocketsCartesian3D {
         ModelTemplate::<Cartesian3D>::new(self)
     }
 }
+
+/// Sockets for a model to be used in a hexagonal grid. See [`crate::grid::hexagonal::coordinates::HexDirection`] for the orientation of each side.
+pub enum SocketsHexagonal {
+    /// The model has only 1 socket, and its is the same in all directions.
+    Mono(Socket),
+    /// The model has 1 socket per side.
+    Simple {
+        /// socket on the east side
+        east: Socket,
+        /// socket on the north-east side
+        north_east: Socket,
+        /// socket on the north-west side
+        north_west: Socket,
+        /// socket on the west side
+        west: Socket,
+        /// socket on the south-west side
+        south_west: Socket,
+        /// socket on the south-east side
+        south_east: Socket,
+    },
+    /// The model has multiple sockets per side.
+    Multiple {
+        /// sockets on the east side
+        east: Vec<Socket>,
+        /// sockets on the north-east side
+        north_east: Vec<Socket>,
+        /// sockets on the north-west side
+        north_west: Vec<Socket>,
+        /// sockets on the west side
+        west: Vec<Socket>,
+        /// sockets on the south-west side
+        south_west: Vec<Socket>,
+        /// sockets on the south-east side
+        south_east: Vec<Socket>,
+    },
+}
+
+impl From<SocketsHexagonal> for Vec<Vec<Socket>> {
+    fn from(sockets: SocketsHexagonal) -> Self {
+        match sockets {
+            SocketsHexagonal::Mono(socket) => vec![vec![socket]; 6],
+            SocketsHexagonal::Simple {
+                east,
+                north_east,
+                north_west,
+                west,
+                south_west,
+                south_east,
+            } => {
+                vec![
+                    vec![east],
+                    vec![north_east],
+                    vec![north_west],
+                    vec![west],
+                    vec![south_west],
+                    vec![south_east],
+                ]
+            }
+            SocketsHexagonal::Multiple {
+                east,
+                north_east,
+                north_west,
+                west,
+                south_west,
+                south_east,
+            } => {
+                vec![east, north_east, north_west, west, south_west, south_east]
+            }
+        }
+    }
+}
+
+impl From<SocketsHexagonal> for ModelTemplate<Hexagonal> {
+    fn from(sockets: SocketsHexagonal) -> Self {
+        sockets.to_template()
+    }
+}
+
+impl SocketsHexagonal {
+    /// Creates a [`ModelTemplate`] from its sockets definition, with default values for the other members: weight is [`super::model::DEFAULT_MODEL_WEIGHT`] and the model will not be rotated.
+    pub fn to_template(self) -> ModelTemplate<Hexagonal> {
+        ModelTemplate::<Hexagonal>::new(self)
+    }
+}
+
+/// Sockets for a model to be used in a [`crate::grid::graph::GraphGrid`], in the order of the direction slots of its [`GraphCoordinates`].
+pub enum SocketsGraph {
+    /// The model has 1 socket per direction slot.
+    Simple(Vec<Socket>),
+    /// The model has multiple sockets per direction slot.
+    Multiple(Vec<Vec<Socket>>),
+}
+
+impl From<SocketsGraph> for Vec<Vec<Socket>> {
+    fn from(sockets: SocketsGraph) -> Self {
+        match sockets {
+            SocketsGraph::Simple(sockets) => sockets.into_iter().map(|s| vec![s]).collect(),
+            SocketsGraph::Multiple(sockets) => sockets,
+        }
+    }
+}
+
+impl From<SocketsGraph> for ModelTemplate<GraphCoordinates> {
+    fn from(sockets: SocketsGraph) -> Self {
+        sockets.to_template()
+    }
+}
+
+impl SocketsGraph {
+    /// The model has only 1 socket, and its is the same in all the direction slots of `coord_system`.
+    pub fn mono(socket: Socket, coord_system: &GraphCoordinates) -> Self {
+        SocketsGraph::Simple(vec![socket; coord_system.directions_count()])
+    }
+
+    /// Creates a [`ModelTemplate`] from its sockets definition, with default values for the other members: weight is [`super::model::DEFAULT_MODEL_WEIGHT`].
+    pub fn to_template(self) -> ModelTemplate<GraphCoordinates> {
+        ModelTemplate::<GraphCoordinates>::new(self)
+    }
+}
@@ -218,3 +218,63 @@ impl fmt::Display for CartesianPosition {
         write!(f, "x: {}, y: {}, z: {}", self.x, self.y, self.z)
     }
 }
+
+/// Represents a signed position in world space, in grid units. Used by a [`super::grid::CartesianGrid`] with an origin, to reference nodes independently of the grid (or chunk) they belong to.
+#[derive(Default, Hash, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct WorldPosition {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl WorldPosition {
+    pub const ZERO: WorldPosition = WorldPosition { x: 0, y: 0, z: 0 };
+
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn new_xy(x: i32, y: i32) -> Self {
+        Self { x, y, z: 0 }
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+}
+
+impl From<(i32, i32)> for WorldPosition {
+    fn from(xy: (i32, i32)) -> Self {
+        WorldPosition::new(xy.0, xy.1, 0)
+    }
+}
+
+impl From<(i32, i32, i32)> for WorldPosition {
+    fn from(xyz: (i32, i32, i32)) -> Self {
+        WorldPosition::new(xyz.0, xyz.1, xyz.2)
+    }
+}
+
+impl std::ops::Add<GridDelta> for WorldPosition {
+    type Output = WorldPosition;
+
+    fn add(self, rhs: GridDelta) -> WorldPosition {
+        WorldPosition::new(self.x + rhs.dx, self.y + rhs.dy, self.z + rhs.dz)
+    }
+}
+
+impl std::ops::Sub for WorldPosition {
+    type Output = GridDelta;
+
+    fn sub(self, rhs: WorldPosition) -> GridDelta {
+        GridDelta::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl fmt::Display for WorldPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x: {}, y: {}, z: {}", self.x, self.y, self.z)
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(missing_docs)]
 
-use std::{fmt, ops::Range};
+use std::{collections::HashSet, fmt, ops::Range};
 
 use crate::grid::{
     coordinate_system::CoordinateSystem,
@@ -9,7 +9,7 @@ use crate::grid::{
 };
 
 use super::coordinates::{
-    Cartesian2D, Cartesian3D, CartesianCoordinates, CartesianPosition, GridDelta,
+    Cartesian2D, Cartesian3D, CartesianCoordinates, CartesianPosition, GridDelta, WorldPosition,
 };
 
 #[cfg(feature = "bevy")]
@@ -18,6 +18,8 @@ use bevy::ecs::component::Component;
 use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
 
 /// Definition of a Cartesian grid (2D or 3D).
+///
+/// Positions in the grid are unsigned and start at (0,0,0). A grid can also be placed in a larger world through its origin (see [`CartesianGrid::with_origin`]): the node at (0,0,0) is then at the [`WorldPosition`] `origin`.
 #[derive(Clone)]
 #[cfg_attr(feature = "bevy", derive(Component, Default))]
 #[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
@@ -30,6 +32,7 @@ pub struct CartesianGrid<C: CoordinateSystem> {
     looping_z: bool,
     pub(crate) coord_system: C,
     size_xy: u32,
+    origin: WorldPosition,
 }
 
 impl<C: CartesianCoordinates> Grid<C> for CartesianGrid<C> {
@@ -81,8 +84,14 @@ impl<C: CartesianCoordinates> fmt::Display for CartesianGrid<C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "( size: {} {} {}, looping: {} {} {} )",
-            self.size_x, self.size_y, self.size_z, self.looping_x, self.looping_y, self.looping_z
+            "( size: {} {} {}, looping: {} {} {}, origin: {} )",
+            self.size_x,
+            self.size_y,
+            self.size_z,
+            self.looping_x,
+            self.looping_y,
+            self.looping_z,
+            self.origin
         )
     }
 }
@@ -148,9 +157,112 @@ impl<C: CartesianCoordinates> CartesianGrid<C> {
             looping_z,
             size_xy: size_x * size_y,
             coord_system,
+            origin: WorldPosition::ZERO,
         }
     }
 
+    /// Places the grid in the world: the node at (0,0,0) in the grid will be at `origin` in the world.
+    ///
+    /// ### Example
+    ///
+    /// A grid used as the chunk (-1, 2) of a world made of 16x16 chunks
+    /// ```
+    /// use procedural_tilemaps_core::grid::cartesian::{coordinates::{CartesianPosition, WorldPosition}, grid::CartesianGrid};
+    ///
+    /// let grid = CartesianGrid::new_cartesian_2d(16, 16, false, false);
+    /// let grid = grid.clone().with_origin(grid.chunk_origin(&WorldPosition::new_xy(-1, 2)));
+    ///
+    /// let world_pos = WorldPosition::new_xy(-3, 40);
+    /// assert_eq!(grid.chunk_coords(&world_pos), WorldPosition::new_xy(-1, 2));
+    /// assert_eq!(grid.local_pos_from_world_pos(&world_pos), CartesianPosition::new_xy(13, 8));
+    /// assert_eq!(grid.world_pos_from_pos(&CartesianPosition::new_xy(13, 8)), world_pos);
+    /// ```
+    pub fn with_origin(mut self, origin: WorldPosition) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Sets the world position of the node at (0,0,0) in the grid.
+    pub fn set_origin(&mut self, origin: WorldPosition) {
+        self.origin = origin;
+    }
+
+    /// Returns the world position of the node at (0,0,0) in the grid.
+    #[inline]
+    pub fn origin(&self) -> WorldPosition {
+        self.origin
+    }
+
+    /// Returns the world position of a position of the grid.
+    #[inline]
+    pub fn world_pos_from_pos(&self, grid_position: &CartesianPosition) -> WorldPosition {
+        WorldPosition::new(
+            self.origin.x + grid_position.x as i32,
+            self.origin.y + grid_position.y as i32,
+            self.origin.z + grid_position.z as i32,
+        )
+    }
+
+    /// Returns the world position of the node at `index` in the grid.
+    #[inline]
+    pub fn world_pos_from_index(&self, index: GridIndex) -> WorldPosition {
+        self.world_pos_from_pos(&self.pos_from_index(index))
+    }
+
+    /// Returns the position in the grid of a world position, or `None` if the world position is outside of the grid.
+    ///
+    /// Looping axes are not considered: the world position must be within the bounds of the grid.
+    pub fn try_pos_from_world_pos(&self, world_pos: &WorldPosition) -> Option<CartesianPosition> {
+        let delta = *world_pos - self.origin;
+        let (x, y, z) = (
+            u32::try_from(delta.dx).ok()?,
+            u32::try_from(delta.dy).ok()?,
+            u32::try_from(delta.dz).ok()?,
+        );
+        (x < self.size_x && y < self.size_y && z < self.size_z)
+            .then_some(CartesianPosition::new(x, y, z))
+    }
+
+    /// Returns the index in the grid of a world position, or `None` if the world position is outside of the grid.
+    #[inline]
+    pub fn try_index_from_world_pos(&self, world_pos: &WorldPosition) -> Option<GridIndex> {
+        self.try_pos_from_world_pos(world_pos)
+            .map(|pos| self.index_from_pos(&pos))
+    }
+
+    /// Returns the coordinates of the chunk containing a world position, when the world is split in chunks the size of this grid with a chunk starting at (0,0,0).
+    pub fn chunk_coords(&self, world_pos: &WorldPosition) -> WorldPosition {
+        WorldPosition::new(
+            world_pos.x.div_euclid(self.size_x as i32),
+            world_pos.y.div_euclid(self.size_y as i32),
+            world_pos.z.div_euclid(self.size_z as i32),
+        )
+    }
+
+    /// Returns the world position of the first node of a chunk, when the world is split in chunks the size of this grid with a chunk starting at (0,0,0).
+    pub fn chunk_origin(&self, chunk_coords: &WorldPosition) -> WorldPosition {
+        WorldPosition::new(
+            chunk_coords.x * self.size_x as i32,
+            chunk_coords.y * self.size_y as i32,
+            chunk_coords.z * self.size_z as i32,
+        )
+    }
+
+    /// Returns the position of a world position within its chunk, when the world is split in chunks the size of this grid with a chunk starting at (0,0,0).
+    pub fn local_pos_from_world_pos(&self, world_pos: &WorldPosition) -> CartesianPosition {
+        CartesianPosition::new(
+            world_pos.x.rem_euclid(self.size_x as i32) as u32,
+            world_pos.y.rem_euclid(self.size_y as i32) as u32,
+            world_pos.z.rem_euclid(self.size_z as i32) as u32,
+        )
+    }
+
+    /// Returns the index of a world position within its chunk, when the world is split in chunks the size of this grid with a chunk starting at (0,0,0).
+    #[inline]
+    pub fn local_index_from_world_pos(&self, world_pos: &WorldPosition) -> GridIndex {
+        self.index_from_pos(&self.local_pos_from_world_pos(world_pos))
+    }
+
     #[inline]
     pub fn size(&self) -> (u32, u32, u32) {
         (self.size_x, self.size_y, self.size_z)
@@ -171,11 +283,22 @@ impl<C: CartesianCoordinates> CartesianGrid<C> {
         self.size_z
     }
 
+    #[inline]
+    pub fn looping(&self) -> (bool, bool, bool) {
+        (self.looping_x, self.looping_y, self.looping_z)
+    }
+
     #[inline]
     pub fn indexes(&self) -> Range<GridIndex> {
         0..self.total_size()
     }
 
+    /// Returns `true` if the position is within the bounds of the grid.
+    #[inline]
+    pub fn contains(&self, pos: &CartesianPosition) -> bool {
+        pos.x < self.size_x && pos.y < self.size_y && pos.z < self.size_z
+    }
+
     #[inline]
     pub fn index_from_coords(&self, x: u32, y: u32, z: u32) -> GridIndex {
         (x + y * self.size_x + z * self.size_xy) as GridIndex
@@ -224,12 +347,7 @@ impl<C: CartesianCoordinates> CartesianGrid<C> {
             (self.looping_z, &mut next.2, self.size_z),
         ] {
             if looping {
-                if *coord < 0 {
-                    *coord += size as i64;
-                }
-                if *coord >= size as i64 {
-                    *coord -= size as i64;
-                }
+                *coord = coord.rem_euclid(size as i64);
             } else if *coord < 0 || *coord >= size as i64 {
                 return None;
             }
@@ -241,6 +359,98 @@ impl<C: CartesianCoordinates> CartesianGrid<C> {
         })
     }
 
+    /// Iterates over the neighbours of `grid_position` in all the directions of the coordinate system, respecting the looping flags of the grid.
+    pub fn neighbours(
+        &self,
+        grid_position: &CartesianPosition,
+    ) -> impl Iterator<Item = (Direction, CartesianPosition)> + '_ {
+        let grid_position = *grid_position;
+        self.coord_system
+            .directions()
+            .iter()
+            .zip(self.coord_system.deltas())
+            .filter_map(move |(direction, delta)| {
+                self.get_next_pos(&grid_position, delta)
+                    .map(|pos| (*direction, pos))
+            })
+    }
+
+    /// Returns the positions at a distance of exactly `radius` from `center` in the XY plane, using the Chebyshev distance (a square ring), counter-clockwise from the bottom-left corner.
+    ///
+    /// Positions are wrapped on looping axes and skipped on non-looping ones. Each position is returned once, even when a ring wraps onto itself.
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::grid::cartesian::{coordinates::CartesianPosition, grid::CartesianGrid};
+    ///
+    /// let grid = CartesianGrid::new_cartesian_2d(3, 3, true, true);
+    /// let center = CartesianPosition::new_xy(1, 1);
+    /// assert_eq!(grid.ring(&center, 1).len(), 8);
+    /// // A ring larger than the grid wraps onto itself several times
+    /// assert_eq!(grid.ring(&center, 5).len(), 8);
+    ///
+    /// let grid = CartesianGrid::new_cartesian_2d(3, 3, false, false);
+    /// assert!(grid.ring(&center, 5).is_empty());
+    /// ```
+    pub fn ring(&self, center: &CartesianPosition, radius: u32) -> Vec<CartesianPosition> {
+        let r = radius as i32;
+        let mut deltas = Vec::new();
+        if r == 0 {
+            deltas.push(GridDelta::new(0, 0, 0));
+        } else {
+            deltas.extend((-r..r).map(|dx| GridDelta::new(dx, -r, 0)));
+            deltas.extend((-r..r).map(|dy| GridDelta::new(r, dy, 0)));
+            deltas.extend((-r..r).map(|dx| GridDelta::new(-dx, r, 0)));
+            deltas.extend((-r..r).map(|dy| GridDelta::new(-r, -dy, 0)));
+        }
+        let mut visited = HashSet::new();
+        deltas
+            .iter()
+            .filter_map(|delta| self.get_next_pos(center, delta))
+            .filter(|pos| visited.insert(*pos))
+            .collect()
+    }
+
+    /// Returns the positions on a straight line from `start` to `start + delta` (both included), rasterized like a Bresenham line.
+    ///
+    /// The line is wrapped on looping axes and stops at the first position out of the bounds of the grid.
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::grid::cartesian::{
+    ///     coordinates::{CartesianPosition, GridDelta},
+    ///     grid::CartesianGrid,
+    /// };
+    ///
+    /// let grid = CartesianGrid::new_cartesian_2d(3, 3, true, false);
+    /// let start = CartesianPosition::new_xy(1, 1);
+    /// // A line longer than the grid wraps around it several times
+    /// let xs: Vec<u32> = grid.line(&start, &GridDelta::new(-7, 0, 0)).map(|pos| pos.x).collect();
+    /// assert_eq!(xs, vec![1, 0, 2, 1, 0, 2, 1, 0]);
+    ///
+    /// // On a non-looping axis, the line stops at the border of the grid
+    /// assert_eq!(grid.line(&start, &GridDelta::new(0, 7, 0)).count(), 2);
+    /// ```
+    pub fn line(
+        &self,
+        start: &CartesianPosition,
+        delta: &GridDelta,
+    ) -> impl Iterator<Item = CartesianPosition> + '_ {
+        let start = *start;
+        let delta = *delta;
+        let steps = delta.dx.abs().max(delta.dy.abs()).max(delta.dz.abs());
+        (0..=steps).map_while(move |step| {
+            let partial = |d: i32| match steps {
+                0 => 0,
+                _ => (f64::from(d) * f64::from(step) / f64::from(steps)).round() as i32,
+            };
+            self.get_next_pos(
+                &start,
+                &GridDelta::new(partial(delta.dx), partial(delta.dy), partial(delta.dz)),
+            )
+        })
+    }
+
     pub fn direction(&self, from: GridIndex, to: GridIndex) -> Direction {
         let from = self.pos_from_index(from);
         let to = self.pos_from_index(to);
@@ -285,3 +495,11 @@ impl<C: CartesianCoordinates> NodeRef<C, CartesianGrid<C>> for (u32, u32, u32) {
         grid.index_from_coords(self.0, self.1, self.2)
     }
 }
+
+/// Panics if the world position is outside of the grid. See [`CartesianGrid::try_index_from_world_pos`].
+impl<C: CartesianCoordinates> NodeRef<C, CartesianGrid<C>> for WorldPosition {
+    fn to_index(&self, grid: &CartesianGrid<C>) -> GridIndex {
+        grid.try_index_from_world_pos(self)
+            .expect("World position should be within the grid")
+    }
+}
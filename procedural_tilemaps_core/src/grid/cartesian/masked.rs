@@ -0,0 +1,259 @@
+#![allow(missing_docs)]
+
+use std::{fmt, ops::Range};
+
+use crate::grid::{
+    coordinate_system::CoordinateSystem,
+    grid::{Grid, GridData, GridIndex, NodeRef},
+};
+
+use super::{
+    coordinates::{CartesianCoordinates, CartesianPosition, WorldPosition},
+    grid::CartesianGrid,
+};
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::component::Component;
+#[cfg(feature = "reflect")]
+use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
+
+/// A [`CartesianGrid`] where only some of the cells are active, to generate irregular shapes (islands, arenas, levels with holes, ...).
+///
+/// - Inactive cells are not part of the grid: they are never generated nor reported by a [`crate::generator::Generator`].
+/// - For an active cell, a neighbour that is inactive is treated like a non-looping border.
+/// - Node indexes are dense: they go from `0` to the number of active cells. Use [`MaskedGrid::full_index`] and [`MaskedGrid::dense_index`] to convert from/to the indexes of the underlying [`CartesianGrid`].
+///
+/// ### Example
+///
+/// A circular arena
+/// ```
+/// use procedural_tilemaps_core::grid::{CartesianGrid, MaskedGrid};
+///
+/// let grid = CartesianGrid::new_cartesian_2d(20, 20, false, false);
+/// let arena = MaskedGrid::new(grid, |pos| {
+///     let (dx, dy) = (pos.x as i32 - 10, pos.y as i32 - 10);
+///     dx * dx + dy * dy <= 100
+/// });
+/// assert!(arena.is_active(&(10, 10).into()));
+/// assert!(!arena.is_active(&(0, 0).into()));
+/// ```
+///
+/// Referencing an inactive cell in a generator returns an error
+/// ```
+/// use procedural_tilemaps_core::prelude::*;
+/// use procedural_tilemaps_core::{grid::MaskedGrid, NodeSetError};
+///
+/// let mut sockets = SocketCollection::new();
+/// let a = sockets.create();
+/// sockets.add_connection(a, vec![a]);
+/// let mut models = ModelCollection::<Cartesian2D>::new();
+/// models.create(SocketsCartesian2D::Mono(a));
+/// let rules = RulesBuilder::new_cartesian_2d(models, sockets).build().unwrap();
+///
+/// let grid = MaskedGrid::new(CartesianGrid::new_cartesian_2d(4, 4, false, false), |pos| pos.x > 0);
+/// let mut generator = GeneratorBuilder::new().with_rules(rules).with_grid(grid).build().unwrap();
+/// assert!(matches!(
+///     generator.set_and_propagate((0, 0), (0, ModelRotation::Rot0), false),
+///     Err(NodeSetError::InvalidNodeRef)
+/// ));
+/// assert!(generator.set_and_propagate((1, 0), (0, ModelRotation::Rot0), false).is_ok());
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "bevy", derive(Component, Default))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct MaskedGrid<C: CoordinateSystem> {
+    grid: CartesianGrid<C>,
+    /// For each cell of `grid`, its index in the masked grid if it is active
+    dense_indexes: Vec<Option<GridIndex>>,
+    /// For each active cell, its index in `grid`
+    full_indexes: Vec<GridIndex>,
+}
+
+impl<C: CartesianCoordinates> Grid<C> for MaskedGrid<C> {
+    type Position = CartesianPosition;
+
+    #[inline]
+    fn coord_system(&self) -> &C {
+        &self.grid.coord_system
+    }
+
+    #[inline]
+    fn directions_count(&self) -> usize {
+        self.grid.directions_count()
+    }
+
+    #[inline]
+    fn total_size(&self) -> usize {
+        self.full_indexes.len()
+    }
+
+    fn get_neighbours_in_all_directions(
+        &self,
+        grid_index: GridIndex,
+        neighbours_buffer: &mut Vec<Option<GridIndex>>,
+    ) {
+        let pos = self.pos_from_index(grid_index);
+        for dir in self.grid.coord_system.directions() {
+            neighbours_buffer[usize::from(*dir)] = self
+                .grid
+                .get_next_index_in_direction(&pos, *dir)
+                .and_then(|full_index| self.dense_indexes[full_index]);
+        }
+    }
+
+    /// Returns the index corresponding to `pos`.
+    ///
+    /// Panics if `pos` is not an active cell, see [`MaskedGrid::try_index_from_pos`] for a non-panicking version. The [`crate::generator::Generator`] methods taking a [`NodeRef`] return a [`crate::NodeSetError::InvalidNodeRef`] for an inactive cell instead of panicking.
+    #[inline]
+    fn index_from_pos(&self, grid_position: &CartesianPosition) -> GridIndex {
+        self.try_index_from_pos(grid_position)
+            .expect("Position is not an active cell of the masked grid")
+    }
+
+    #[inline]
+    fn pos_from_index(&self, grid_index: GridIndex) -> CartesianPosition {
+        self.grid.pos_from_index(self.full_indexes[grid_index])
+    }
+}
+
+impl<C: CartesianCoordinates> fmt::Display for MaskedGrid<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "( {}, active cells: {} )",
+            self.grid,
+            self.full_indexes.len()
+        )
+    }
+}
+
+impl<C: CartesianCoordinates> MaskedGrid<C> {
+    /// Creates a new masked grid from a [`CartesianGrid`] where the cells for which `is_active` returns `true` are active.
+    pub fn new<F: Fn(&CartesianPosition) -> bool>(grid: CartesianGrid<C>, is_active: F) -> Self {
+        let mut dense_indexes = Vec::with_capacity(grid.total_size());
+        let mut full_indexes = Vec::new();
+        for full_index in grid.indexes() {
+            if is_active(&grid.pos_from_index(full_index)) {
+                dense_indexes.push(Some(full_indexes.len()));
+                full_indexes.push(full_index);
+            } else {
+                dense_indexes.push(None);
+            }
+        }
+        Self {
+            grid,
+            dense_indexes,
+            full_indexes,
+        }
+    }
+
+    /// Creates a new masked grid from a [`GridData`] of booleans, where the `true` cells are active.
+    pub fn from_mask(mask: &GridData<C, bool, CartesianGrid<C>>) -> Self {
+        let grid = mask.grid().clone();
+        Self::new(grid, |pos| *mask.get(mask.grid().index_from_pos(pos)))
+    }
+
+    /// Returns the underlying [`CartesianGrid`], which includes the inactive cells.
+    #[inline]
+    pub fn cartesian_grid(&self) -> &CartesianGrid<C> {
+        &self.grid
+    }
+
+    #[inline]
+    pub fn indexes(&self) -> Range<GridIndex> {
+        0..self.total_size()
+    }
+
+    #[inline]
+    pub fn is_active(&self, pos: &CartesianPosition) -> bool {
+        self.try_index_from_pos(pos).is_some()
+    }
+
+    /// Returns the index of the active cell at `pos`, or `None` if the cell is inactive or outside the grid.
+    pub fn try_index_from_pos(&self, pos: &CartesianPosition) -> Option<GridIndex> {
+        let (size_x, size_y, size_z) = self.grid.size();
+        if pos.x >= size_x || pos.y >= size_y || pos.z >= size_z {
+            return None;
+        }
+        self.dense_indexes[self.grid.index_from_pos(pos)]
+    }
+
+    /// Converts an index of the underlying [`CartesianGrid`] into an index of this grid. Returns `None` if the cell is inactive.
+    #[inline]
+    pub fn dense_index(&self, full_index: GridIndex) -> Option<GridIndex> {
+        self.dense_indexes.get(full_index).copied().flatten()
+    }
+
+    /// Converts an index of this grid into an index of the underlying [`CartesianGrid`].
+    #[inline]
+    pub fn full_index(&self, dense_index: GridIndex) -> GridIndex {
+        self.full_indexes[dense_index]
+    }
+
+    /// Spreads the data of the active cells onto the whole underlying [`CartesianGrid`]. Inactive cells are set to `None`.
+    pub fn to_cartesian_grid_data<D: Clone>(
+        &self,
+        data: &GridData<C, D, MaskedGrid<C>>,
+    ) -> GridData<C, Option<D>, CartesianGrid<C>> {
+        let mut full_data = self.grid.new_grid_data(None);
+        for (dense_index, value) in data.iter().enumerate() {
+            full_data.set_raw(self.full_indexes[dense_index], Some(value.clone()));
+        }
+        full_data
+    }
+
+    pub fn default_grid_data<D: Default + Clone>(&self) -> GridData<C, D, MaskedGrid<C>> {
+        GridData::new(self.clone(), vec![D::default(); self.total_size()])
+    }
+
+    pub fn new_grid_data<D: Clone>(&self, value: D) -> GridData<C, D, MaskedGrid<C>> {
+        GridData::new(self.clone(), vec![value; self.total_size()])
+    }
+}
+
+/// [`NodeRef::to_index`] panics if the position is not an active cell, the generator entry points use [`NodeRef::try_to_index`] and return an error instead.
+impl<C: CartesianCoordinates> NodeRef<C, MaskedGrid<C>> for CartesianPosition {
+    fn to_index(&self, grid: &MaskedGrid<C>) -> GridIndex {
+        grid.index_from_pos(self)
+    }
+
+    fn try_to_index(&self, grid: &MaskedGrid<C>) -> Option<GridIndex> {
+        grid.try_index_from_pos(self)
+    }
+}
+
+impl<C: CartesianCoordinates> NodeRef<C, MaskedGrid<C>> for (u32, u32) {
+    fn to_index(&self, grid: &MaskedGrid<C>) -> GridIndex {
+        grid.index_from_pos(&CartesianPosition::new(self.0, self.1, 0))
+    }
+
+    fn try_to_index(&self, grid: &MaskedGrid<C>) -> Option<GridIndex> {
+        grid.try_index_from_pos(&CartesianPosition::new(self.0, self.1, 0))
+    }
+}
+
+impl<C: CartesianCoordinates> NodeRef<C, MaskedGrid<C>> for (u32, u32, u32) {
+    fn to_index(&self, grid: &MaskedGrid<C>) -> GridIndex {
+        grid.index_from_pos(&CartesianPosition::new(self.0, self.1, self.2))
+    }
+
+    fn try_to_index(&self, grid: &MaskedGrid<C>) -> Option<GridIndex> {
+        grid.try_index_from_pos(&CartesianPosition::new(self.0, self.1, self.2))
+    }
+}
+
+/// [`NodeRef::to_index`] panics if the world position is outside of the grid or inactive.
+impl<C: CartesianCoordinates> NodeRef<C, MaskedGrid<C>> for WorldPosition {
+    fn to_index(&self, grid: &MaskedGrid<C>) -> GridIndex {
+        let pos = grid
+            .grid
+            .try_pos_from_world_pos(self)
+            .expect("World position should be within the grid");
+        grid.index_from_pos(&pos)
+    }
+
+    fn try_to_index(&self, grid: &MaskedGrid<C>) -> Option<GridIndex> {
+        let pos = grid.grid.try_pos_from_world_pos(self)?;
+        grid.try_index_from_pos(&pos)
+    }
+}
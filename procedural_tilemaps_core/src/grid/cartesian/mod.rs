@@ -2,6 +2,12 @@
 
 pub mod coordinates;
 pub mod grid;
+pub mod masked;
+pub mod navigation;
+pub mod region;
 
 pub use coordinates::*;
 pub use grid::*;
+pub use masked::*;
+pub use navigation::*;
+pub use region::*;
@@ -0,0 +1,370 @@
+//! Navigation utilities on a [`CartesianGrid`]: per-node traversal costs, A* pathfinding, Dijkstra distance fields and flood-fill reachability.
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::{component::Component, resource::Resource};
+
+use crate::{
+    generator::{
+        model::{ModelInstance, ModelMetadata},
+        rules::Rules,
+    },
+    grid::{
+        direction::Direction,
+        grid::{Grid, GridData, GridIndex, NodeRef},
+    },
+};
+
+use super::{coordinates::CartesianCoordinates, grid::CartesianGrid};
+
+/// Traversal costs of the nodes of a [`CartesianGrid`], used to find paths through a generated map.
+///
+/// Each node has an optional cost: the cost to enter it from any of its neighbours, or [`None`] if it cannot be traversed. Costs should be positive or zero.
+///
+/// Movement follows the directions of the coordinate system and the looping flags of the grid. In 3d, moving between z layers can be disabled with [`NavigationGrid::with_layer_traversal`].
+///
+/// With the `bevy` feature, a [`NavigationGrid`] can be used as a `Resource` or a `Component`.
+///
+/// ### Example
+/// ```
+/// use procedural_tilemaps_core::prelude::*;
+///
+/// let grid = CartesianGrid::new_cartesian_2d(5, 3, false, false);
+/// // A wall on x = 2, with a gap at the top
+/// let walls = [(2, 0), (2, 1)];
+/// let mut tiles = grid.new_grid_data(false);
+/// for wall in walls {
+///     tiles.set(wall, true);
+/// }
+/// let navigation = NavigationGrid::from_fn(&tiles, |is_wall| (!is_wall).then_some(1.));
+///
+/// let path = navigation.path((0, 0), (4, 0)).unwrap();
+/// assert_eq!(path.cost, 8.);
+/// assert_eq!(path.nodes.len(), 9);
+/// assert!(path.nodes.contains(&grid.get_index_2d(2, 2)));
+///
+/// let distances = navigation.distance_field([(0, 0)]);
+/// assert_eq!(*distances.get(grid.get_index_2d(4, 0)), Some(8.));
+/// assert_eq!(*distances.get(grid.get_index_2d(2, 0)), None);
+///
+/// let reachable = navigation.reachable((0, 0));
+/// assert!(reachable.get(grid.get_index_2d(4, 2)));
+/// assert!(!reachable.get(grid.get_index_2d(2, 1)));
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "bevy", derive(Component, Resource))]
+pub struct NavigationGrid<C: CartesianCoordinates> {
+    costs: GridData<C, Option<f32>, CartesianGrid<C>>,
+    layer_traversal: bool,
+    /// Lowest cost of a traversable node, used to keep the A* heuristic admissible
+    min_cost: f32,
+}
+
+/// A path found by [`NavigationGrid::path`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct NavigationPath {
+    /// Nodes of the path, from the start node to the goal node (both included)
+    pub nodes: Vec<GridIndex>,
+    /// Total cost of the path: the sum of the costs of all its nodes except the start node
+    pub cost: f32,
+}
+
+impl<C: CartesianCoordinates> NavigationGrid<C> {
+    /// Creates a new [`NavigationGrid`] from the traversal cost of each node. [`None`] marks a node which cannot be traversed.
+    pub fn new(costs: GridData<C, Option<f32>, CartesianGrid<C>>) -> Self {
+        let min_cost = costs
+            .iter()
+            .flatten()
+            .copied()
+            .fold(f32::INFINITY, f32::min);
+        Self {
+            costs,
+            layer_traversal: true,
+            min_cost: if min_cost.is_finite() { min_cost } else { 0. },
+        }
+    }
+
+    /// Creates a new [`NavigationGrid`] with the traversal cost returned by `cost` for each node of `grid_data`
+    pub fn from_fn<D>(
+        grid_data: &GridData<C, D, CartesianGrid<C>>,
+        cost: impl FnMut(&D) -> Option<f32>,
+    ) -> Self {
+        Self::new(grid_data.map(cost))
+    }
+
+    /// Creates a new [`NavigationGrid`] from a generated grid, with the traversal cost returned by `cost` for the [`ModelMetadata`] of each node's model in `rules`.
+    ///
+    /// See [`GridData::map_metadata`].
+    pub fn from_metadata(
+        grid_data: &GridData<C, ModelInstance, CartesianGrid<C>>,
+        rules: &Rules<C>,
+        cost: impl FnMut(&ModelMetadata) -> Option<f32>,
+    ) -> Self {
+        Self::new(grid_data.map_metadata(rules, cost))
+    }
+
+    /// Creates a new [`NavigationGrid`] from a generated grid, where only the nodes whose model has the tag `walkable_tag` in `rules` can be traversed, all with a cost of `1`.
+    ///
+    /// ### Example
+    /// ```
+    /// use procedural_tilemaps_core::prelude::*;
+    ///
+    /// let mut models = ModelCollection::<Cartesian2D>::new();
+    /// let mut sockets = SocketCollection::new();
+    /// let (grass, water) = (sockets.create(), sockets.create());
+    /// sockets.add_connections(vec![(grass, vec![grass, water]), (water, vec![water])]);
+    /// models.create(SocketsCartesian2D::Mono(grass)).with_tag("walkable");
+    /// models.create(SocketsCartesian2D::Mono(water)).with_tag("water");
+    /// let rules = RulesBuilder::new_cartesian_2d(models, sockets).build().unwrap();
+    ///
+    /// let grid = CartesianGrid::new_cartesian_2d(10, 10, false, false);
+    /// let mut generator = GeneratorBuilder::new()
+    ///     .with_rules(rules)
+    ///     .with_grid(grid.clone())
+    ///     .build()
+    ///     .unwrap();
+    /// let (_, grid_data) = generator.generate_grid().unwrap();
+    ///
+    /// let navigation = NavigationGrid::from_tag(&grid_data, generator.rules(), "walkable");
+    /// let walkable = grid_data.tag_mask(generator.rules(), "walkable");
+    /// for index in grid.indexes() {
+    ///     assert_eq!(navigation.cost(index).is_some(), *walkable.get(index));
+    /// }
+    /// ```
+    pub fn from_tag(
+        grid_data: &GridData<C, ModelInstance, CartesianGrid<C>>,
+        rules: &Rules<C>,
+        walkable_tag: &str,
+    ) -> Self {
+        Self::from_metadata(grid_data, rules, |metadata| {
+            metadata.has_tag(walkable_tag).then_some(1.)
+        })
+    }
+
+    /// Specify whether moving between z layers is allowed. Defaults to `true`.
+    ///
+    /// When disabled, each z layer is navigated independently.
+    pub fn with_layer_traversal(mut self, layer_traversal: bool) -> Self {
+        self.layer_traversal = layer_traversal;
+        self
+    }
+
+    /// Returns the grid of this [`NavigationGrid`]
+    #[inline]
+    pub fn grid(&self) -> &CartesianGrid<C> {
+        self.costs.grid()
+    }
+
+    /// Returns the traversal costs of all the nodes
+    #[inline]
+    pub fn costs(&self) -> &GridData<C, Option<f32>, CartesianGrid<C>> {
+        &self.costs
+    }
+
+    /// Returns the traversal cost of a node, or [`None`] if it cannot be traversed
+    #[inline]
+    pub fn cost<N: NodeRef<C, CartesianGrid<C>>>(&self, node: N) -> Option<f32> {
+        *self.costs.get(node.to_index(self.grid()))
+    }
+
+    /// Sets the traversal cost of a node. [`None`] marks the node as not traversable.
+    pub fn set_cost<N: NodeRef<C, CartesianGrid<C>>>(&mut self, node: N, cost: Option<f32>) {
+        if let Some(cost) = cost {
+            self.min_cost = self.min_cost.min(cost);
+        }
+        self.costs.set(node, cost);
+    }
+
+    /// Calls `f` with the index and the traversal cost of every traversable neighbour of `index`
+    fn for_each_neighbour(&self, index: GridIndex, mut f: impl FnMut(GridIndex, f32)) {
+        let grid = self.grid();
+        for (direction, pos) in grid.neighbours(&grid.pos_from_index(index)) {
+            if !self.layer_traversal
+                && matches!(direction, Direction::ZForward | Direction::ZBackward)
+            {
+                continue;
+            }
+            let neighbour = grid.index_from_pos(&pos);
+            if let Some(cost) = self.costs.get(neighbour) {
+                f(neighbour, *cost);
+            }
+        }
+    }
+
+    /// Lower bound of the cost of a path between two nodes: their manhattan distance (wrapped on looping axes) times the lowest node cost
+    fn heuristic(&self, from: GridIndex, to: GridIndex) -> f32 {
+        let grid = self.grid();
+        let (from, to) = (grid.pos_from_index(from), grid.pos_from_index(to));
+        let (size_x, size_y, size_z) = grid.size();
+        let (looping_x, looping_y, looping_z) = grid.looping();
+        let axis_distance = |a: u32, b: u32, size: u32, looping: bool| {
+            let distance = a.abs_diff(b);
+            match looping {
+                true => distance.min(size - distance),
+                false => distance,
+            }
+        };
+        let distance = axis_distance(from.x, to.x, size_x, looping_x)
+            + axis_distance(from.y, to.y, size_y, looping_y)
+            + axis_distance(from.z, to.z, size_z, looping_z);
+        distance as f32 * self.min_cost
+    }
+
+    /// Finds a path of minimal cost from `start` to `goal` with A*.
+    ///
+    /// Returns [`None`] if `start` or `goal` cannot be traversed, or if there is no path between them.
+    pub fn path<S: NodeRef<C, CartesianGrid<C>>, G: NodeRef<C, CartesianGrid<C>>>(
+        &self,
+        start: S,
+        goal: G,
+    ) -> Option<NavigationPath> {
+        let start = start.to_index(self.grid());
+        let goal = goal.to_index(self.grid());
+        if self.costs.get(start).is_none() || self.costs.get(goal).is_none() {
+            return None;
+        }
+
+        let total_size = self.grid().total_size();
+        let mut costs = vec![f32::INFINITY; total_size];
+        let mut previous = vec![None; total_size];
+        let mut open = BinaryHeap::new();
+        costs[start] = 0.;
+        open.push(OpenNode {
+            priority: self.heuristic(start, goal),
+            index: start,
+        });
+
+        while let Some(OpenNode { priority, index }) = open.pop() {
+            if index == goal {
+                let mut nodes = vec![goal];
+                let mut current = goal;
+                while let Some(prev) = previous[current] {
+                    nodes.push(prev);
+                    current = prev;
+                }
+                nodes.reverse();
+                return Some(NavigationPath {
+                    nodes,
+                    cost: costs[goal],
+                });
+            }
+            // Outdated entry, this node was already reached with a lower cost
+            if priority > costs[index] + self.heuristic(index, goal) {
+                continue;
+            }
+            self.for_each_neighbour(index, |neighbour, cost| {
+                let neighbour_cost = costs[index] + cost;
+                if neighbour_cost < costs[neighbour] {
+                    costs[neighbour] = neighbour_cost;
+                    previous[neighbour] = Some(index);
+                    open.push(OpenNode {
+                        priority: neighbour_cost + self.heuristic(neighbour, goal),
+                        index: neighbour,
+                    });
+                }
+            });
+        }
+        None
+    }
+
+    /// Computes, with Dijkstra's algorithm, the cost of the cheapest path from any of the `sources` to every node.
+    ///
+    /// Nodes which cannot be reached are [`None`]. Sources which cannot be traversed are ignored.
+    ///
+    /// Following decreasing distances from any reachable node leads to the closest source, which makes the result usable as a flow field.
+    pub fn distance_field<N: NodeRef<C, CartesianGrid<C>>>(
+        &self,
+        sources: impl IntoIterator<Item = N>,
+    ) -> GridData<C, Option<f32>, CartesianGrid<C>> {
+        let mut distances = self.grid().new_grid_data(None);
+        let mut open = BinaryHeap::new();
+        for source in sources {
+            let index = source.to_index(self.grid());
+            if self.costs.get(index).is_some() {
+                distances.set_raw(index, Some(0.));
+                open.push(OpenNode {
+                    priority: 0.,
+                    index,
+                });
+            }
+        }
+
+        while let Some(OpenNode { priority, index }) = open.pop() {
+            if distances
+                .get(index)
+                .is_some_and(|distance| priority > distance)
+            {
+                continue;
+            }
+            self.for_each_neighbour(index, |neighbour, cost| {
+                let neighbour_distance = priority + cost;
+                if distances
+                    .get(neighbour)
+                    .is_none_or(|distance| neighbour_distance < distance)
+                {
+                    distances.set_raw(neighbour, Some(neighbour_distance));
+                    open.push(OpenNode {
+                        priority: neighbour_distance,
+                        index: neighbour,
+                    });
+                }
+            });
+        }
+        distances
+    }
+
+    /// Returns, for every node, whether it can be reached from `start` through traversable nodes, ignoring costs.
+    ///
+    /// Every node is unreachable if `start` cannot be traversed.
+    pub fn reachable<N: NodeRef<C, CartesianGrid<C>>>(
+        &self,
+        start: N,
+    ) -> GridData<C, bool, CartesianGrid<C>> {
+        let mut reached = self.grid().new_grid_data(false);
+        let start = start.to_index(self.grid());
+        if self.costs.get(start).is_none() {
+            return reached;
+        }
+        reached.set_raw(start, true);
+        let mut to_visit = vec![start];
+        while let Some(index) = to_visit.pop() {
+            self.for_each_neighbour(index, |neighbour, _cost| {
+                if !reached.get(neighbour) {
+                    reached.set_raw(neighbour, true);
+                    to_visit.push(neighbour);
+                }
+            });
+        }
+        reached
+    }
+}
+
+/// Entry of the open set of the pathfinding algorithms, ordered so that the [`BinaryHeap`] pops the lowest priority first
+struct OpenNode {
+    priority: f32,
+    index: GridIndex,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
@@ -0,0 +1,311 @@
+//! Region utilities for a [`GridData`] on a [`CartesianGrid`]: sub-region views, blitting, resizing and positional iteration.
+
+use crate::{
+    generator::model::{ModelFlip, ModelRotation},
+    grid::{
+        direction::Direction,
+        grid::{Grid, GridData},
+    },
+};
+
+use super::{
+    coordinates::{CartesianCoordinates, CartesianPosition, GridDelta, WorldPosition},
+    grid::CartesianGrid,
+};
+
+/// A read-only view of a box-shaped region of a [`GridData`] on a [`CartesianGrid`], created with [`GridData::view`].
+///
+/// Positions in the view are local to the region: (0,0,0) is the minimum corner of the region.
+pub struct GridDataView<'a, C: CartesianCoordinates, D> {
+    data: &'a GridData<C, D, CartesianGrid<C>>,
+    min: CartesianPosition,
+    size: (u32, u32, u32),
+}
+
+impl<'a, C: CartesianCoordinates, D> GridDataView<'a, C, D> {
+    /// Returns the position of the minimum corner of the region, in the viewed grid.
+    #[inline]
+    pub fn min(&self) -> CartesianPosition {
+        self.min
+    }
+
+    /// Returns the size of the region.
+    #[inline]
+    pub fn size(&self) -> (u32, u32, u32) {
+        self.size
+    }
+
+    /// Returns the value at a position local to the region, or `None` if the position is outside of the region.
+    pub fn get(&self, local_pos: &CartesianPosition) -> Option<&'a D> {
+        if local_pos.x < self.size.0 && local_pos.y < self.size.1 && local_pos.z < self.size.2 {
+            Some(
+                self.data.get(
+                    self.data
+                        .grid()
+                        .index_from_pos(&self.to_grid_pos(local_pos)),
+                ),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over the values of the region with their position local to the region, in index order.
+    pub fn iter_with_positions(&self) -> impl Iterator<Item = (CartesianPosition, &'a D)> + '_ {
+        let (size_x, size_y, size_z) = self.size;
+        (0..size_z).flat_map(move |z| {
+            (0..size_y).flat_map(move |y| {
+                (0..size_x).map(move |x| {
+                    let local_pos = CartesianPosition::new(x, y, z);
+                    let index = self
+                        .data
+                        .grid()
+                        .index_from_pos(&self.to_grid_pos(&local_pos));
+                    (local_pos, self.data.get(index))
+                })
+            })
+        })
+    }
+
+    #[inline]
+    fn to_grid_pos(&self, local_pos: &CartesianPosition) -> CartesianPosition {
+        CartesianPosition::new(
+            self.min.x + local_pos.x,
+            self.min.y + local_pos.y,
+            self.min.z + local_pos.z,
+        )
+    }
+}
+
+impl<'a, C: CartesianCoordinates, D: Clone> GridDataView<'a, C, D> {
+    /// Copies the region into a new non-looping [`GridData`]. The origin of the new grid is the world position of the region, see [`CartesianGrid::origin`].
+    pub fn to_grid_data(&self) -> GridData<C, D, CartesianGrid<C>> {
+        let source_grid = self.data.grid();
+        let grid = CartesianGrid::new(
+            self.size.0,
+            self.size.1,
+            self.size.2,
+            false,
+            false,
+            false,
+            source_grid.coord_system().clone(),
+        )
+        .with_origin(source_grid.world_pos_from_pos(&self.min));
+        GridData::new(
+            grid,
+            self.iter_with_positions()
+                .map(|(_, value)| value.clone())
+                .collect(),
+        )
+    }
+}
+
+impl<C: CartesianCoordinates, D> GridData<C, D, CartesianGrid<C>> {
+    /// Iterates over all the values with their position in the grid, in index order.
+    pub fn iter_with_positions(&self) -> impl Iterator<Item = (CartesianPosition, &D)> {
+        self.iter()
+            .enumerate()
+            .map(|(index, value)| (self.grid().pos_from_index(index), value))
+    }
+
+    /// Returns a read-only view of the box-shaped region starting at `min` and of size `size`.
+    ///
+    /// Panics if the region is not entirely within the grid.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use procedural_tilemaps_core::grid::cartesian::{coordinates::CartesianPosition, grid::CartesianGrid};
+    ///
+    /// let grid = CartesianGrid::new_cartesian_2d(10, 10, false, false);
+    /// let mut data = grid.new_grid_data(0);
+    /// data.set((3, 4), 7);
+    ///
+    /// let room = data.view(&CartesianPosition::new_xy(2, 2), (4, 4, 1));
+    /// assert_eq!(room.get(&CartesianPosition::new_xy(1, 2)), Some(&7));
+    /// assert_eq!(room.iter_with_positions().filter(|(_, v)| **v == 7).count(), 1);
+    /// ```
+    pub fn view(&self, min: &CartesianPosition, size: (u32, u32, u32)) -> GridDataView<'_, C, D> {
+        let grid_size = self.grid().size();
+        assert!(
+            min.x + size.0 <= grid_size.0
+                && min.y + size.1 <= grid_size.1
+                && min.z + size.2 <= grid_size.2,
+            "Region should be within the grid"
+        );
+        GridDataView {
+            data: self,
+            min: *min,
+            size,
+        }
+    }
+
+    /// Iterates over the neighbours of `pos` with their direction and position, respecting the looping flags of the grid.
+    pub fn neighbours(
+        &self,
+        pos: &CartesianPosition,
+    ) -> impl Iterator<Item = (Direction, CartesianPosition, &D)> {
+        self.grid()
+            .neighbours(pos)
+            .map(|(direction, pos)| (direction, pos, self.get(self.grid().index_from_pos(&pos))))
+    }
+
+    /// Iterates over the values on the ring of radius `radius` around `center`. See [`CartesianGrid::ring`].
+    pub fn ring(
+        &self,
+        center: &CartesianPosition,
+        radius: u32,
+    ) -> impl Iterator<Item = (CartesianPosition, &D)> {
+        self.grid()
+            .ring(center, radius)
+            .into_iter()
+            .map(|pos| (pos, self.get(self.grid().index_from_pos(&pos))))
+    }
+
+    /// Iterates over the values on the line from `start` to `start + delta`. See [`CartesianGrid::line`].
+    pub fn line(
+        &self,
+        start: &CartesianPosition,
+        delta: &GridDelta,
+    ) -> impl Iterator<Item = (CartesianPosition, &D)> {
+        self.grid()
+            .line(start, delta)
+            .map(|pos| (pos, self.get(self.grid().index_from_pos(&pos))))
+    }
+}
+
+impl<C: CartesianCoordinates, D: Clone> GridData<C, D, CartesianGrid<C>> {
+    /// Copies the box-shaped region starting at `min` and of size `size` into a new non-looping [`GridData`]. The new grid keeps the world positions of the nodes, see [`CartesianGrid::origin`].
+    ///
+    /// Panics if the region is not entirely within the grid.
+    pub fn crop(
+        &self,
+        min: &CartesianPosition,
+        size: (u32, u32, u32),
+    ) -> GridData<C, D, CartesianGrid<C>> {
+        self.view(min, size).to_grid_data()
+    }
+
+    /// Returns a copy of the grid data with `before` nodes added before the minimum corner and `after` nodes added after the maximum corner on each axis, filled with `value`.
+    ///
+    /// The new grid keeps the looping flags and the world positions of the nodes, see [`CartesianGrid::origin`].
+    pub fn pad(
+        &self,
+        before: (u32, u32, u32),
+        after: (u32, u32, u32),
+        value: D,
+    ) -> GridData<C, D, CartesianGrid<C>> {
+        let (size_x, size_y, size_z) = self.grid().size();
+        let origin = self.grid().origin()
+            + GridDelta::new(-(before.0 as i32), -(before.1 as i32), -(before.2 as i32));
+        let mut padded = self.resized_grid_data(
+            (
+                size_x + before.0 + after.0,
+                size_y + before.1 + after.1,
+                size_z + before.2 + after.2,
+            ),
+            origin,
+            value,
+        );
+        padded.blit(
+            self,
+            &GridDelta::new(before.0 as i32, before.1 as i32, before.2 as i32),
+        );
+        padded
+    }
+
+    /// Returns a copy of the grid data with a new size. Nodes are kept at the same position from (0,0,0), nodes outside of the new size are dropped and new nodes are filled with `value`.
+    ///
+    /// The new grid keeps the looping flags and the origin of the grid.
+    pub fn resize(&self, size: (u32, u32, u32), value: D) -> GridData<C, D, CartesianGrid<C>> {
+        let mut resized = self.resized_grid_data(size, self.grid().origin(), value);
+        resized.blit(self, &GridDelta::default());
+        resized
+    }
+
+    /// Copies all the values of `source` into this grid data, `source` (0,0,0) being copied at `offset`. Values falling outside of the grid are ignored.
+    pub fn blit(&mut self, source: &GridData<C, D, CartesianGrid<C>>, offset: &GridDelta) {
+        self.blit_transformed(source, offset, ModelRotation::Rot0, ModelFlip::None);
+    }
+
+    /// Same as [`GridData::blit`], but `source` is first mirrored by `flip` then rotated counter-clockwise by `rotation` in the XY plane (like a model rotated around the Z axis). `offset` is the position of the minimum corner of the transformed `source`.
+    ///
+    /// Values are copied as is: if they hold orientations themselves (such as model instances), they are not transformed.
+    ///
+    /// ### Example
+    ///
+    /// Stamp an L-shaped prefab rotated by 90°
+    /// ```
+    /// use procedural_tilemaps_core::generator::model::{ModelFlip, ModelRotation};
+    /// use procedural_tilemaps_core::grid::cartesian::{coordinates::GridDelta, grid::CartesianGrid};
+    ///
+    /// let mut prefab = CartesianGrid::new_cartesian_2d(2, 3, false, false).new_grid_data('.');
+    /// prefab.set((0, 0), '#');
+    /// prefab.set((1, 0), '#');
+    /// prefab.set((0, 1), '#');
+    /// prefab.set((0, 2), '#');
+    ///
+    /// let mut map = CartesianGrid::new_cartesian_2d(5, 5, false, false).new_grid_data(' ');
+    /// map.blit_transformed(&prefab, &GridDelta::new(1, 1, 0), ModelRotation::Rot90, ModelFlip::None);
+    ///
+    /// // The 3 tiles long arm now goes along the X axis
+    /// let row = |y: u32| (0..5).map(|x| *map.get(map.grid().index_from_coords(x, y, 0))).collect::<String>();
+    /// assert_eq!(row(1), " ### ");
+    /// assert_eq!(row(2), " ..# ");
+    /// ```
+    pub fn blit_transformed(
+        &mut self,
+        source: &GridData<C, D, CartesianGrid<C>>,
+        offset: &GridDelta,
+        rotation: ModelRotation,
+        flip: ModelFlip,
+    ) {
+        let (source_x, source_y, _) = source.grid().size();
+        for (source_pos, value) in source.iter_with_positions() {
+            let (mut x, mut y) = (source_pos.x, source_pos.y);
+            if flip.flips_x() {
+                x = source_x - 1 - x;
+            }
+            if flip.flips_y() {
+                y = source_y - 1 - y;
+            }
+            let (mut size_x, mut size_y) = (source_x, source_y);
+            for _ in 0..rotation.index() {
+                (x, y) = (size_y - 1 - y, x);
+                (size_x, size_y) = (size_y, size_x);
+            }
+            let (Ok(x), Ok(y), Ok(z)) = (
+                u32::try_from(i64::from(x) + i64::from(offset.dx)),
+                u32::try_from(i64::from(y) + i64::from(offset.dy)),
+                u32::try_from(i64::from(source_pos.z) + i64::from(offset.dz)),
+            ) else {
+                continue;
+            };
+            let dest_pos = CartesianPosition::new(x, y, z);
+            if self.grid().contains(&dest_pos) {
+                let index = self.grid().index_from_pos(&dest_pos);
+                self.set_raw(index, value.clone());
+            }
+        }
+    }
+
+    fn resized_grid_data(
+        &self,
+        size: (u32, u32, u32),
+        origin: WorldPosition,
+        value: D,
+    ) -> GridData<C, D, CartesianGrid<C>> {
+        let (looping_x, looping_y, looping_z) = self.grid().looping();
+        let grid = CartesianGrid::new(
+            size.0,
+            size.1,
+            size.2,
+            looping_x,
+            looping_y,
+            looping_z,
+            self.grid().coord_system().clone(),
+        )
+        .with_origin(origin);
+        grid.new_grid_data(value)
+    }
+}
@@ -5,7 +5,7 @@ use super::direction::DirectionTrait;
 /// Represents a coordinate system used by the grid types.
 pub trait CoordinateSystem: Default + Clone + Sync + Send + 'static {
     /// Direction type associated with this coordinate system.
-    type Direction: DirectionTrait;
+    type Direction: DirectionTrait + Send + Sync;
 
     /// Returns the available directions.
     fn directions(&self) -> &'static [Self::Direction];
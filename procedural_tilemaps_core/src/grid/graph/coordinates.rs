@@ -0,0 +1,107 @@
+#![allow(missing_docs)]
+
+use crate::grid::{
+    coordinate_system::CoordinateSystem,
+    direction::{DirectionIndex, DirectionTrait},
+};
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::component::Component;
+
+/// A user-defined direction slot of a [`GraphCoordinates`], which knows the index of its opposite slot.
+///
+/// An edge leaving a node from a slot always enters its neighbour from the opposite slot. A slot can be its own opposite (for example for undirected "link" edges).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct GraphDirection {
+    index: DirectionIndex,
+    opposite: DirectionIndex,
+}
+
+impl GraphDirection {
+    /// Creates a direction slot with index `index` whose opposite slot has index `opposite`.
+    pub const fn new(index: DirectionIndex, opposite: DirectionIndex) -> Self {
+        Self { index, opposite }
+    }
+
+    /// Returns the index of this direction slot.
+    pub fn index(&self) -> DirectionIndex {
+        self.index
+    }
+}
+
+impl DirectionTrait for GraphDirection {
+    fn opposite(&self) -> GraphDirection {
+        GraphDirection {
+            index: self.opposite,
+            opposite: self.index,
+        }
+    }
+
+    /// Graphs have no rotation axis: models on a graph are never rotated.
+    fn rotation_basis(&self) -> &'static [GraphDirection] {
+        &[]
+    }
+}
+
+impl From<GraphDirection> for DirectionIndex {
+    fn from(value: GraphDirection) -> Self {
+        value.index
+    }
+}
+
+/// Coordinate system with user-defined direction slots, to be used with a [`super::GraphGrid`].
+///
+/// ### Example
+///
+/// A road network where each node can connect to a node "in front" and "behind" it, plus a side road
+/// ```
+/// use procedural_tilemaps_core::grid::graph::{GraphCoordinates, GraphDirection};
+///
+/// const FRONT: GraphDirection = GraphDirection::new(0, 1);
+/// const BACK: GraphDirection = GraphDirection::new(1, 0);
+/// const SIDE: GraphDirection = GraphDirection::new(2, 2);
+///
+/// let coord_system = GraphCoordinates::new(&[FRONT, BACK, SIDE]);
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct GraphCoordinates {
+    directions: &'static [GraphDirection],
+}
+
+impl GraphCoordinates {
+    /// Creates a new graph coordinate system from its direction slots.
+    ///
+    /// Panics if `directions[i]` does not have the index `i`, or if the opposites are not symmetrical.
+    pub fn new(directions: &'static [GraphDirection]) -> Self {
+        for (index, direction) in directions.iter().enumerate() {
+            assert_eq!(
+                direction.index, index,
+                "Direction slot at position {index} has index {}",
+                direction.index
+            );
+            assert!(
+                direction.opposite < directions.len()
+                    && directions[direction.opposite].opposite == index,
+                "Direction slot {index} has an invalid opposite {}",
+                direction.opposite
+            );
+        }
+        Self { directions }
+    }
+}
+
+impl CoordinateSystem for GraphCoordinates {
+    type Direction = GraphDirection;
+
+    #[inline]
+    fn directions(&self) -> &'static [GraphDirection] {
+        self.directions
+    }
+
+    #[inline]
+    fn directions_count(&self) -> usize {
+        self.directions.len()
+    }
+}
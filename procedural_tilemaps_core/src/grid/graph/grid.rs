@@ -0,0 +1,219 @@
+#![allow(missing_docs)]
+
+use std::{fmt, ops::Range};
+
+use crate::{
+    grid::{
+        coordinate_system::CoordinateSystem,
+        direction::{DirectionIndex, DirectionTrait},
+        grid::{Grid, GridData, GridIndex},
+    },
+    GraphGridError,
+};
+
+use super::coordinates::GraphCoordinates;
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::component::Component;
+
+/// A grid where nodes are connected by user-defined edges, to run a generation on non-lattice structures (room graphs, road networks, Voronoi regions, ...).
+///
+/// Each edge leaves a node from a direction slot of the [`GraphCoordinates`] and enters its neighbour from the opposite slot. Each slot of a node has at most one neighbour, a slot without a neighbour is treated like a non-looping border.
+///
+/// Positions in a [`GraphGrid`] are simply the node indexes.
+///
+/// ### Example
+///
+/// Three rooms connected in a line
+/// ```
+/// use procedural_tilemaps_core::grid::graph::{GraphCoordinates, GraphDirection, GraphGrid};
+///
+/// const EXIT: GraphDirection = GraphDirection::new(0, 1);
+/// const ENTRANCE: GraphDirection = GraphDirection::new(1, 0);
+///
+/// let grid = GraphGrid::from_edges(
+///     GraphCoordinates::new(&[EXIT, ENTRANCE]),
+///     vec![
+///         vec![(EXIT.index(), 1)],
+///         vec![(ENTRANCE.index(), 0), (EXIT.index(), 2)],
+///         vec![(ENTRANCE.index(), 1)],
+///     ],
+/// )
+/// .unwrap();
+/// ```
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct GraphGrid {
+    coord_system: GraphCoordinates,
+    nodes_count: usize,
+    /// `neighbours[node_index * directions_count + direction]` is the neighbour of `node_index` in `direction`
+    neighbours: Vec<Option<GridIndex>>,
+}
+
+impl Grid<GraphCoordinates> for GraphGrid {
+    type Position = GridIndex;
+
+    #[inline]
+    fn coord_system(&self) -> &GraphCoordinates {
+        &self.coord_system
+    }
+
+    #[inline]
+    fn directions_count(&self) -> usize {
+        self.coord_system.directions_count()
+    }
+
+    #[inline]
+    fn total_size(&self) -> usize {
+        self.nodes_count
+    }
+
+    fn get_neighbours_in_all_directions(
+        &self,
+        grid_index: GridIndex,
+        neighbours_buffer: &mut Vec<Option<GridIndex>>,
+    ) {
+        let directions_count = self.directions_count();
+        neighbours_buffer.copy_from_slice(
+            &self.neighbours[grid_index * directions_count..(grid_index + 1) * directions_count],
+        );
+    }
+
+    #[inline]
+    fn index_from_pos(&self, grid_position: &GridIndex) -> GridIndex {
+        *grid_position
+    }
+
+    #[inline]
+    fn pos_from_index(&self, grid_index: GridIndex) -> GridIndex {
+        grid_index
+    }
+}
+
+impl fmt::Display for GraphGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "( nodes: {}, directions: {} )",
+            self.nodes_count,
+            self.directions_count()
+        )
+    }
+}
+
+impl GraphGrid {
+    /// Creates a graph of `nodes_count` nodes, without any edges. Use [`GraphGrid::connect`] to add edges.
+    pub fn new(coord_system: GraphCoordinates, nodes_count: usize) -> Self {
+        Self {
+            nodes_count,
+            neighbours: vec![None; nodes_count * coord_system.directions_count()],
+            coord_system,
+        }
+    }
+
+    /// Creates a graph from the list of `(direction_slot, neighbour)` edges of each node: `edges[node_index]` holds the edges leaving `node_index`.
+    ///
+    /// Every edge must have its reverse edge: if `a` has `b` as a neighbour in a slot, `b` must have `a` as a neighbour in the opposite slot.
+    pub fn from_edges(
+        coord_system: GraphCoordinates,
+        edges: Vec<Vec<(DirectionIndex, GridIndex)>>,
+    ) -> Result<Self, GraphGridError> {
+        let mut grid = GraphGrid::new(coord_system, edges.len());
+        let directions_count = grid.directions_count();
+        for (node_index, node_edges) in edges.iter().enumerate() {
+            for &(direction, neighbour) in node_edges {
+                if direction >= directions_count {
+                    return Err(GraphGridError::InvalidDirection(node_index, direction));
+                }
+                if neighbour >= grid.nodes_count {
+                    return Err(GraphGridError::InvalidNeighbour(node_index, neighbour));
+                }
+                let slot = &mut grid.neighbours[node_index * directions_count + direction];
+                if slot.is_some() {
+                    return Err(GraphGridError::DuplicateEdge(node_index, direction));
+                }
+                *slot = Some(neighbour);
+            }
+        }
+        for node_index in grid.indexes() {
+            for direction in grid.coord_system.directions() {
+                if let Some(neighbour) = grid.neighbour(node_index, direction.index()) {
+                    let opposite = usize::from(direction.opposite());
+                    if grid.neighbour(neighbour, opposite) != Some(node_index) {
+                        return Err(GraphGridError::MissingReverseEdge(
+                            node_index,
+                            direction.index(),
+                            neighbour,
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(grid)
+    }
+
+    /// Connects `from` to `to` through the slot `direction` of `from`, and `to` to `from` through the opposite slot.
+    ///
+    /// Previous edges using those slots are replaced: their former neighbours lose their reverse edge, so that every edge of the graph stays two-way. Panics if `direction`, `from` or `to` are invalid.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use procedural_tilemaps_core::grid::graph::{GraphCoordinates, GraphDirection, GraphGrid};
+    ///
+    /// const EXIT: GraphDirection = GraphDirection::new(0, 1);
+    /// const ENTRANCE: GraphDirection = GraphDirection::new(1, 0);
+    ///
+    /// let mut grid = GraphGrid::new(GraphCoordinates::new(&[EXIT, ENTRANCE]), 4);
+    /// grid.connect(0, EXIT.index(), 1);
+    /// grid.connect(2, EXIT.index(), 3);
+    /// // Reuses the EXIT slot of 0 and the ENTRANCE slot of 3
+    /// grid.connect(0, EXIT.index(), 3);
+    ///
+    /// assert_eq!(grid.neighbour(0, EXIT.index()), Some(3));
+    /// assert_eq!(grid.neighbour(3, ENTRANCE.index()), Some(0));
+    /// assert_eq!(grid.neighbour(1, ENTRANCE.index()), None);
+    /// assert_eq!(grid.neighbour(2, EXIT.index()), None);
+    /// ```
+    pub fn connect(&mut self, from: GridIndex, direction: DirectionIndex, to: GridIndex) {
+        assert!(from < self.nodes_count && to < self.nodes_count);
+        let direction = self.coord_system.directions()[direction];
+        let opposite = direction.opposite();
+        let directions_count = self.directions_count();
+        if let Some(old) = self.neighbours[from * directions_count + direction.index()] {
+            let old_slot = &mut self.neighbours[old * directions_count + opposite.index()];
+            if *old_slot == Some(from) {
+                *old_slot = None;
+            }
+        }
+        if let Some(old) = self.neighbours[to * directions_count + opposite.index()] {
+            let old_slot = &mut self.neighbours[old * directions_count + direction.index()];
+            if *old_slot == Some(to) {
+                *old_slot = None;
+            }
+        }
+        self.neighbours[from * directions_count + direction.index()] = Some(to);
+        self.neighbours[to * directions_count + opposite.index()] = Some(from);
+    }
+
+    /// Returns the neighbour of `node_index` in the slot `direction`, if any.
+    #[inline]
+    pub fn neighbour(&self, node_index: GridIndex, direction: DirectionIndex) -> Option<GridIndex> {
+        self.neighbours[node_index * self.directions_count() + direction]
+    }
+
+    #[inline]
+    pub fn indexes(&self) -> Range<GridIndex> {
+        0..self.total_size()
+    }
+
+    pub fn default_grid_data<D: Default + Clone>(
+        &self,
+    ) -> GridData<GraphCoordinates, D, GraphGrid> {
+        GridData::new(self.clone(), vec![D::default(); self.total_size()])
+    }
+
+    pub fn new_grid_data<D: Clone>(&self, value: D) -> GridData<GraphCoordinates, D, GraphGrid> {
+        GridData::new(self.clone(), vec![value; self.total_size()])
+    }
+}
@@ -0,0 +1,7 @@
+//! Coordinate system and grid implementation for arbitrary adjacency graphs.
+
+pub mod coordinates;
+pub mod grid;
+
+pub use coordinates::*;
+pub use grid::*;
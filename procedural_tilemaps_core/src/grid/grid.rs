@@ -118,6 +118,11 @@ where
     pub fn indexes(&self) -> std::ops::Range<usize> {
         0..self.grid.total_size()
     }
+
+    /// Returns a new [`GridData`] on the same grid, holding the result of `f` for each stored value.
+    pub fn map<T>(&self, f: impl FnMut(&D) -> T) -> GridData<C, T, G> {
+        GridData::new(self.grid.clone(), self.data.iter().map(f).collect())
+    }
 }
 
 impl<C: CoordinateSystem, D: Clone, G: Grid<C>> GridData<C, D, G> {
@@ -133,6 +138,13 @@ impl<C: CoordinateSystem, D: Clone, G: Grid<C>> GridData<C, D, G> {
 pub trait NodeRef<C: CoordinateSystem, G: Grid<C>> {
     /// Returns the backing index for this reference.
     fn to_index(&self, grid: &G) -> GridIndex;
+
+    /// Returns the backing index for this reference, or `None` if it does not reference a node of `grid` (such as an inactive cell of a [`crate::grid::MaskedGrid`]).
+    ///
+    /// Defaults to [`NodeRef::to_index`].
+    fn try_to_index(&self, grid: &G) -> Option<GridIndex> {
+        Some(self.to_index(grid))
+    }
 }
 
 impl<C: CoordinateSystem, G: Grid<C>> NodeRef<C, G> for GridIndex {
@@ -0,0 +1,139 @@
+#![allow(missing_docs)]
+
+use std::fmt;
+
+use crate::grid::{
+    coordinate_system::CoordinateSystem,
+    direction::{DirectionIndex, DirectionTrait},
+};
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::component::Component;
+#[cfg(feature = "reflect")]
+use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
+
+/// 2D hexagonal coordinate system with six directions, for pointy-top hexagons.
+#[derive(Default, Debug, Clone, Copy)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct Hexagonal;
+impl CoordinateSystem for Hexagonal {
+    type Direction = HexDirection;
+
+    #[inline]
+    fn directions(&self) -> &'static [HexDirection] {
+        HEXAGONAL_DIRECTIONS
+    }
+
+    #[inline]
+    fn directions_count(&self) -> usize {
+        HEXAGONAL_DIRECTIONS.len()
+    }
+}
+
+/// Enumerates the six neighbour directions of a pointy-top hexagon, counter-clockwise starting from the east (X+).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub enum HexDirection {
+    /// X+
+    #[default]
+    East = 0,
+    /// 60° counter-clockwise from X+
+    NorthEast = 1,
+    /// 120° counter-clockwise from X+
+    NorthWest = 2,
+    /// X-
+    West = 3,
+    /// 240° counter-clockwise from X+
+    SouthWest = 4,
+    /// 300° counter-clockwise from X+
+    SouthEast = 5,
+}
+
+impl DirectionTrait for HexDirection {
+    fn opposite(&self) -> HexDirection {
+        HEXAGONAL_DIRECTIONS[(*self as usize + 3) % HEXAGONAL_DIRECTIONS.len()]
+    }
+
+    /// All the hexagonal directions, there is only one rotation axis in a hexagonal grid.
+    fn rotation_basis(&self) -> &'static [HexDirection] {
+        HEXAGONAL_DIRECTIONS
+    }
+}
+
+impl From<HexDirection> for DirectionIndex {
+    fn from(value: HexDirection) -> Self {
+        value as DirectionIndex
+    }
+}
+
+/// All directions for a hexagonal system, counter-clockwise.
+pub const HEXAGONAL_DIRECTIONS: &[HexDirection] = &[
+    HexDirection::East,
+    HexDirection::NorthEast,
+    HexDirection::NorthWest,
+    HexDirection::West,
+    HexDirection::SouthWest,
+    HexDirection::SouthEast,
+];
+
+/// Offsets `(dx, dy)` to the neighbour in each direction, for nodes on an even row.
+pub const HEX_EVEN_ROW_DELTAS: &[(i32, i32)] =
+    &[(1, 0), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1)];
+
+/// Offsets `(dx, dy)` to the neighbour in each direction, for nodes on an odd row.
+pub const HEX_ODD_ROW_DELTAS: &[(i32, i32)] = &[(1, 0), (1, 1), (0, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// Represents a position in a hexagonal grid, in "odd-r" offset coordinates: rows go up along Y and odd rows are shifted by half a hexagon towards X+.
+#[derive(Default, Hash, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct HexPosition {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl HexPosition {
+    pub fn new(x: u32, y: u32) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the axial coordinates `(q, r)` of this position.
+    pub fn to_axial(&self) -> (i64, i64) {
+        let (x, y) = (i64::from(self.x), i64::from(self.y));
+        (x - (y - (y & 1)) / 2, y)
+    }
+
+    /// Returns the number of steps between two hexagons (ignoring any looping).
+    pub fn distance(&self, other: &Self) -> u32 {
+        let (q1, r1) = self.to_axial();
+        let (q2, r2) = other.to_axial();
+        let (dq, dr) = (q1 - q2, r1 - r2);
+        ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as u32
+    }
+
+    pub(crate) fn get_delta_position(&self, direction: HexDirection) -> (i64, i64) {
+        let deltas = match self.y % 2 {
+            0 => HEX_EVEN_ROW_DELTAS,
+            _ => HEX_ODD_ROW_DELTAS,
+        };
+        let (dx, dy) = deltas[direction as usize];
+        (
+            i64::from(self.x) + i64::from(dx),
+            i64::from(self.y) + i64::from(dy),
+        )
+    }
+}
+
+impl From<(u32, u32)> for HexPosition {
+    fn from(xy: (u32, u32)) -> Self {
+        HexPosition::new(xy.0, xy.1)
+    }
+}
+
+impl fmt::Display for HexPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x: {}, y: {}", self.x, self.y)
+    }
+}
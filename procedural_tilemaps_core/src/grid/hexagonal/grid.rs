@@ -0,0 +1,186 @@
+#![allow(missing_docs)]
+
+use std::{fmt, ops::Range};
+
+use crate::grid::{
+    coordinate_system::CoordinateSystem,
+    grid::{Grid, GridData, GridIndex, NodeRef},
+};
+
+use super::coordinates::{HexDirection, HexPosition, Hexagonal};
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::component::Component;
+#[cfg(feature = "reflect")]
+use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
+
+/// Definition of a rectangular grid of pointy-top hexagons, in "odd-r" offset coordinates (see [`HexPosition`]).
+#[derive(Clone)]
+#[cfg_attr(feature = "bevy", derive(Component, Default))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct HexGrid {
+    size_x: u32,
+    size_y: u32,
+    looping_x: bool,
+    looping_y: bool,
+    pub(crate) coord_system: Hexagonal,
+}
+
+impl Grid<Hexagonal> for HexGrid {
+    type Position = HexPosition;
+
+    #[inline]
+    fn coord_system(&self) -> &Hexagonal {
+        &self.coord_system
+    }
+
+    #[inline]
+    fn directions_count(&self) -> usize {
+        self.coord_system.directions_count()
+    }
+
+    #[inline]
+    fn total_size(&self) -> usize {
+        (self.size_x * self.size_y) as usize
+    }
+
+    fn get_neighbours_in_all_directions(
+        &self,
+        grid_index: GridIndex,
+        neighbours_buffer: &mut Vec<Option<GridIndex>>,
+    ) {
+        let pos = self.pos_from_index(grid_index);
+        for dir in self.coord_system.directions() {
+            neighbours_buffer[usize::from(*dir)] = self.get_next_index_in_direction(&pos, *dir);
+        }
+    }
+
+    #[inline]
+    fn index_from_pos(&self, grid_position: &HexPosition) -> GridIndex {
+        self.index_from_coords(grid_position.x, grid_position.y)
+    }
+
+    #[inline]
+    fn pos_from_index(&self, grid_index: GridIndex) -> HexPosition {
+        let index = grid_index as u32;
+        HexPosition {
+            x: index % self.size_x,
+            y: index / self.size_x,
+        }
+    }
+}
+
+impl fmt::Display for HexGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "( size: {} {}, looping: {} {} )",
+            self.size_x, self.size_y, self.looping_x, self.looping_y
+        )
+    }
+}
+
+impl HexGrid {
+    /// Creates a new hexagonal grid.
+    ///
+    /// Panics if `looping_y` is `true` and `size_y` is odd: rows alternate between two offsets and cannot wrap around an odd number of rows.
+    pub fn new(size_x: u32, size_y: u32, looping_x: bool, looping_y: bool) -> HexGrid {
+        assert!(
+            !looping_y || size_y.is_multiple_of(2),
+            "A hexagonal grid looping on the y axis needs an even size_y, got {size_y}"
+        );
+        Self {
+            size_x,
+            size_y,
+            looping_x,
+            looping_y,
+            coord_system: Hexagonal,
+        }
+    }
+
+    #[inline]
+    pub fn size(&self) -> (u32, u32) {
+        (self.size_x, self.size_y)
+    }
+
+    #[inline]
+    pub fn size_x(&self) -> u32 {
+        self.size_x
+    }
+
+    #[inline]
+    pub fn size_y(&self) -> u32 {
+        self.size_y
+    }
+
+    #[inline]
+    pub fn looping(&self) -> (bool, bool) {
+        (self.looping_x, self.looping_y)
+    }
+
+    #[inline]
+    pub fn indexes(&self) -> Range<GridIndex> {
+        0..self.total_size()
+    }
+
+    #[inline]
+    pub fn index_from_coords(&self, x: u32, y: u32) -> GridIndex {
+        (x + y * self.size_x) as GridIndex
+    }
+
+    pub fn get_next_index_in_direction(
+        &self,
+        grid_position: &HexPosition,
+        direction: HexDirection,
+    ) -> Option<GridIndex> {
+        self.get_next_pos(grid_position, direction)
+            .map(|pos| self.index_from_pos(&pos))
+    }
+
+    pub fn get_next_pos(
+        &self,
+        grid_position: &HexPosition,
+        direction: HexDirection,
+    ) -> Option<HexPosition> {
+        let mut next = grid_position.get_delta_position(direction);
+        for (looping, coord, size) in [
+            (self.looping_x, &mut next.0, self.size_x),
+            (self.looping_y, &mut next.1, self.size_y),
+        ] {
+            if looping {
+                if *coord < 0 {
+                    *coord += size as i64;
+                }
+                if *coord >= size as i64 {
+                    *coord -= size as i64;
+                }
+            } else if *coord < 0 || *coord >= size as i64 {
+                return None;
+            }
+        }
+        Some(HexPosition {
+            x: next.0 as u32,
+            y: next.1 as u32,
+        })
+    }
+
+    pub fn default_grid_data<D: Default + Clone>(&self) -> GridData<Hexagonal, D, HexGrid> {
+        GridData::new(self.clone(), vec![D::default(); self.total_size()])
+    }
+
+    pub fn new_grid_data<D: Clone>(&self, value: D) -> GridData<Hexagonal, D, HexGrid> {
+        GridData::new(self.clone(), vec![value; self.total_size()])
+    }
+}
+
+impl NodeRef<Hexagonal, HexGrid> for HexPosition {
+    fn to_index(&self, grid: &HexGrid) -> GridIndex {
+        grid.index_from_pos(self)
+    }
+}
+
+impl NodeRef<Hexagonal, HexGrid> for (u32, u32) {
+    fn to_index(&self, grid: &HexGrid) -> GridIndex {
+        grid.index_from_coords(self.0, self.1)
+    }
+}
@@ -0,0 +1,7 @@
+//! Hexagonal coordinate system and grid implementation.
+
+pub mod coordinates;
+pub mod grid;
+
+pub use coordinates::*;
+pub use grid::*;
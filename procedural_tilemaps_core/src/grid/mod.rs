@@ -9,9 +9,15 @@ pub mod coordinate_system;
 #[allow(missing_docs)]
 pub mod direction;
 #[allow(missing_docs)]
+pub mod graph;
+#[allow(missing_docs)]
 pub mod grid;
+#[allow(missing_docs)]
+pub mod hexagonal;
 
 pub use cartesian::*;
 pub use coordinate_system::*;
 pub use direction::*;
+pub use graph::{coordinates::*, grid::*};
 pub use grid::*;
+pub use hexagonal::{coordinates::*, grid::*};
@@ -29,6 +29,55 @@ pub enum RulesBuilderError {
     /// Rules cannot be built without models or sockets
     #[error("Empty models or sockets collection")]
     NoModelsOrSockets,
+    /// A model does not have one list of sockets per direction of the coordinate system
+    #[error("Model {0} has sockets for {1} directions, expected {2}")]
+    InvalidSocketsCount(ModelIndex, usize, usize),
+    /// A model allows rotations or flips, but the coordinate system of the rules cannot rotate nor reflect models
+    #[error(
+        "Model {0} allows rotations or flips, which are not supported by this coordinate system"
+    )]
+    UnsupportedTransformations(ModelIndex),
+}
+
+/// Error returned by a [`grid::graph::GraphGrid`] when it cannot be built from the given edges
+#[derive(thiserror::Error, Debug, Clone, Copy)]
+pub enum GraphGridError {
+    /// An edge uses a direction slot that does not exist in the coordinate system
+    #[error("Node {0} has an edge in direction slot `{1}`, which does not exist")]
+    InvalidDirection(NodeIndex, usize),
+    /// An edge points to a node that does not exist in the graph
+    #[error("Node {0} has an edge to node {1}, which does not exist")]
+    InvalidNeighbour(NodeIndex, NodeIndex),
+    /// A node has more than one edge in the same direction slot
+    #[error("Node {0} has more than one edge in direction slot `{1}`")]
+    DuplicateEdge(NodeIndex, usize),
+    /// An edge does not have its reverse edge in the opposite direction slot
+    #[error("Node {0} has an edge in direction slot `{1}` to node {2}, which has no edge back in the opposite slot")]
+    MissingReverseEdge(NodeIndex, usize, NodeIndex),
+}
+
+/// Error returned by [`generator::rules_definition::RulesDefinition::rules`] when [`generator::rules::Rules`] cannot be built from their definition
+#[cfg(feature = "serde")]
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum RulesDefinitionError {
+    /// A socket name was used without being declared in the sockets of the definition
+    #[error("Unknown socket name: {0}")]
+    UnknownSocket(String),
+    /// A socket name was declared more than once in the sockets of the definition
+    #[error("Duplicate socket name: {0}")]
+    DuplicateSocket(String),
+    /// A rotation is not one of 0, 90, 180 or 270 degrees
+    #[error("Model {0} has an invalid rotation of {1} degrees")]
+    InvalidRotation(ModelIndex, u32),
+    /// A model is missing the sockets of the z axis, required by the coordinate system
+    #[error("Model {0} is missing its z_pos and z_neg sockets")]
+    MissingZSockets(ModelIndex),
+    /// A model has sockets on the z axis, which does not exist in the coordinate system
+    #[error("Model {0} has z_pos or z_neg sockets in 2d rules")]
+    UnexpectedZSockets(ModelIndex),
+    /// The rules could not be built
+    #[error("Invalid rules: {0}")]
+    Rules(#[from] RulesBuilderError),
 }
 
 /// Error returned by a [`generator::Generator`] when a node set operation fails
@@ -43,9 +92,15 @@ pub enum NodeSetError {
     /// An invalid node index was given
     #[error("Invalid node index `{0}`, does not exist in the grid")]
     InvalidNodeIndex(NodeIndex),
+    /// A [`grid::grid::NodeRef`] which does not reference a node of the grid was given, such as an inactive cell of a [`grid::MaskedGrid`]
+    #[error("Invalid node reference, does not exist in the grid")]
+    InvalidNodeRef,
     /// An operation requested to set a model on a node that does not allow it
     #[error("Model variant `{0}` not allowed by the Rules on node {1}")]
     IllegalModel(ModelVariantIndex, NodeIndex),
+    /// A given [`grid::GridData`] does not match the size of the generator's grid
+    #[error("Given grid size {0:?} does not match the expected size {1:?}")]
+    InvalidGridSize(usize, usize),
     /// Wraps a [`GeneratorError`]
     #[error("Generation error: {0}")]
     GenerationError(#[from] GeneratorError),
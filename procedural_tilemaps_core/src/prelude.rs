@@ -2,13 +2,23 @@
 
 pub use crate::generator::{
     builder::GeneratorBuilder,
-    model::{Model, ModelCollection, ModelRotation, ModelTemplate},
+    model::{
+        HexRotation, Model, ModelCollection, ModelFlip, ModelMetadata, ModelRotation, ModelTemplate,
+    },
     node_heuristic::NodeSelectionHeuristic,
     rules::RulesBuilder,
-    socket::{Socket, SocketCollection, SocketsCartesian2D, SocketsCartesian3D},
+    socket::{
+        Socket, SocketCollection, SocketsCartesian2D, SocketsCartesian3D, SocketsGraph,
+        SocketsHexagonal,
+    },
     GenerationStatus, Generator, ModelSelectionHeuristic, RngMode,
 };
 pub use crate::grid::{
-    cartesian::{Cartesian2D, Cartesian3D, CartesianGrid, CartesianPosition, GridDelta},
+    cartesian::{
+        Cartesian2D, Cartesian3D, CartesianGrid, CartesianPosition, GridDelta, MaskedGrid,
+        NavigationGrid, WorldPosition,
+    },
     direction::Direction,
+    graph::{GraphCoordinates, GraphDirection, GraphGrid},
+    hexagonal::{HexDirection, HexGrid, HexPosition, Hexagonal},
 };
@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use procedural_tilemaps_core::generator::{
+    builder::DEFAULT_RETRY_COUNT, node_heuristic::NodeSelectionHeuristic,
+};
+
+use crate::CliError;
+
+pub const USAGE: &str = "\
+Generates a map from a rules file, without Bevy.
+
+Usage: procgen-cli <RULES_FILE> --size <X>x<Y>[x<Z>] [OPTIONS]
+
+The rules file uses the RON format of `bevy_procedural_tilemaps` rules assets.
+A grid with a z size uses 3d rules, which need sockets for the z axis.
+
+Options:
+  --size <X>x<Y>[x<Z>]  Size of the grid (required)
+  --loop-x              Loop the grid on the x axis
+  --loop-y              Loop the grid on the y axis
+  --loop-z              Loop the grid on the z axis
+  --seed <SEED>         Seed of the generation, random by default
+  --heuristic <NAME>    Node selection heuristic: min-entropy (default), min-remaining-value or random
+  --retries <COUNT>     Maximum retry count when a generation fails (default: 50)
+  --json <PATH>         Write the generated models to PATH as JSON
+  --csv <PATH>          Write the generated models to PATH as CSV
+  --quiet               Do not print the ASCII preview
+  -h, --help            Print this help
+
+Exit codes:
+  0  The map was generated
+  1  A file could not be read or written
+  2  Invalid arguments
+  3  Invalid rules file
+  4  The generation failed after all its retries";
+
+/// Size of the grid to generate. A grid without a z size is 2d.
+#[derive(Clone, Copy, Debug)]
+pub struct GridSize {
+    pub x: u32,
+    pub y: u32,
+    pub z: Option<u32>,
+}
+
+/// Parsed command line arguments
+#[derive(Debug)]
+pub struct Args {
+    pub rules_path: PathBuf,
+    pub size: GridSize,
+    pub looping: (bool, bool, bool),
+    pub seed: Option<u64>,
+    pub heuristic: NodeSelectionHeuristic,
+    pub retries: u32,
+    pub json_path: Option<PathBuf>,
+    pub csv_path: Option<PathBuf>,
+    pub quiet: bool,
+}
+
+impl Args {
+    /// Parses the arguments, without the program name. Returns `None` if the help was requested.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, CliError> {
+        let mut args = args.into_iter();
+        let mut rules_path = None;
+        let mut size = None;
+        let mut looping = (false, false, false);
+        let mut seed = None;
+        let mut heuristic = NodeSelectionHeuristic::MinimumEntropy;
+        let mut retries = DEFAULT_RETRY_COUNT;
+        let mut json_path = None;
+        let mut csv_path = None;
+        let mut quiet = false;
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| CliError::Args(format!("Missing value for {}", name)))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--size" => size = Some(parse_size(&value("--size")?)?),
+                "--loop-x" => looping.0 = true,
+                "--loop-y" => looping.1 = true,
+                "--loop-z" => looping.2 = true,
+                "--seed" => seed = Some(parse_number("--seed", &value("--seed")?)?),
+                "--heuristic" => heuristic = parse_heuristic(&value("--heuristic")?)?,
+                "--retries" => retries = parse_number("--retries", &value("--retries")?)?,
+                "--json" => json_path = Some(PathBuf::from(value("--json")?)),
+                "--csv" => csv_path = Some(PathBuf::from(value("--csv")?)),
+                "--quiet" => quiet = true,
+                _ if arg.starts_with('-') => {
+                    return Err(CliError::Args(format!("Unknown option: {}", arg)))
+                }
+                _ if rules_path.is_none() => rules_path = Some(PathBuf::from(arg)),
+                _ => return Err(CliError::Args(format!("Unexpected argument: {}", arg))),
+            }
+        }
+
+        let rules_path =
+            rules_path.ok_or_else(|| CliError::Args("Missing rules file".to_string()))?;
+        let size = size.ok_or_else(|| CliError::Args("Missing --size".to_string()))?;
+        if size.z.is_none() && looping.2 {
+            return Err(CliError::Args(
+                "--loop-z requires a grid with a z size".to_string(),
+            ));
+        }
+        Ok(Some(Self {
+            rules_path,
+            size,
+            looping,
+            seed,
+            heuristic,
+            retries,
+            json_path,
+            csv_path,
+            quiet,
+        }))
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::Args(format!("Invalid value for {}: {}", name, value)))
+}
+
+fn parse_size(value: &str) -> Result<GridSize, CliError> {
+    let sizes = value
+        .split('x')
+        .map(|size| match size.parse::<u32>() {
+            Ok(size) if size > 0 => Ok(size),
+            _ => Err(CliError::Args(format!("Invalid grid size: {}", value))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match sizes[..] {
+        [x, y] => Ok(GridSize { x, y, z: None }),
+        [x, y, z] => Ok(GridSize { x, y, z: Some(z) }),
+        _ => Err(CliError::Args(format!("Invalid grid size: {}", value))),
+    }
+}
+
+fn parse_heuristic(value: &str) -> Result<NodeSelectionHeuristic, CliError> {
+    match value {
+        "min-entropy" => Ok(NodeSelectionHeuristic::MinimumEntropy),
+        "min-remaining-value" => Ok(NodeSelectionHeuristic::MinimumRemainingValue),
+        "random" => Ok(NodeSelectionHeuristic::Random),
+        _ => Err(CliError::Args(format!("Unknown heuristic: {}", value))),
+    }
+}
@@ -0,0 +1,169 @@
+//! Headless command line generator: generates a map from a rules file and writes it as JSON, CSV and an ASCII preview.
+//!
+//! Run `procgen-cli --help` for the usage and the exit codes.
+
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::Path,
+    process::ExitCode,
+};
+
+use procedural_tilemaps_core::{
+    generator::{
+        builder::GeneratorBuilder,
+        rules_definition::{RulesDefinition, RulesDefinitionCoordinates},
+        RngMode,
+    },
+    grid::cartesian::grid::CartesianGrid,
+    GeneratorBuilderError, GeneratorError, NodeSetError, RulesDefinitionError,
+};
+
+use crate::args::{Args, USAGE};
+
+mod args;
+mod output;
+
+/// Errors of the command line generator, each with its own exit code
+#[derive(Debug)]
+pub enum CliError {
+    /// A file could not be read or written
+    Io(String, std::io::Error),
+    /// The command line arguments are invalid
+    Args(String),
+    /// The rules file could not be parsed
+    RulesParsing(ron::error::SpannedError),
+    /// The rules could not be built from the rules file
+    Rules(RulesDefinitionError),
+    /// The generator could not be built
+    Generator(GeneratorBuilderError),
+    /// The generation failed after all its retries
+    Generation(GeneratorError),
+}
+
+impl CliError {
+    /// Exit code of the process for this error
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Io(..) => 1,
+            CliError::Args(_) => 2,
+            CliError::RulesParsing(_) | CliError::Rules(_) | CliError::Generator(_) => 3,
+            CliError::Generation(_) => 4,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Io(path, err) => write!(f, "Could not access {}: {}", path, err),
+            CliError::Args(message) => write!(f, "{}", message),
+            CliError::RulesParsing(err) => write!(f, "Could not parse rules file: {}", err),
+            CliError::Rules(err) => write!(f, "{}", err),
+            CliError::Generator(err) => write!(f, "Could not build the generator: {}", err),
+            CliError::Generation(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            // Ignore write errors such as a closed pipe
+            let _ = writeln!(io::stdout(), "{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::from(err.exit_code());
+        }
+    };
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), CliError> {
+    let rules_file = fs::read_to_string(&args.rules_path)
+        .map_err(|err| CliError::Io(args.rules_path.display().to_string(), err))?;
+    // Same options as the rules assets of `bevy_procedural_tilemaps`
+    let definition: RulesDefinition = ron::Options::default()
+        .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+        .from_str(&rules_file)
+        .map_err(CliError::RulesParsing)?;
+
+    let (x, y, (loop_x, loop_y, loop_z)) = (args.size.x, args.size.y, args.looping);
+    match args.size.z {
+        None => generate(
+            args,
+            &definition,
+            CartesianGrid::new_cartesian_2d(x, y, loop_x, loop_y),
+        ),
+        Some(z) => generate(
+            args,
+            &definition,
+            CartesianGrid::new_cartesian_3d(x, y, z, loop_x, loop_y, loop_z),
+        ),
+    }
+}
+
+fn generate<C: RulesDefinitionCoordinates>(
+    args: &Args,
+    definition: &RulesDefinition,
+    grid: CartesianGrid<C>,
+) -> Result<(), CliError> {
+    let rules = definition.rules::<C>().map_err(CliError::Rules)?;
+    let mut generator = GeneratorBuilder::new()
+        .with_rules(rules)
+        .with_grid(grid)
+        .with_node_heuristic(args.heuristic)
+        .with_max_retry_count(args.retries)
+        .with_rng(match args.seed {
+            Some(seed) => RngMode::Seeded(seed),
+            None => RngMode::RandomSeed,
+        })
+        .build()
+        .map_err(|err| match err {
+            // Initial nodes are generated when building: a contradiction there is a generation failure
+            GeneratorBuilderError::InitialNodeSetError(NodeSetError::GenerationError(err)) => {
+                CliError::Generation(err)
+            }
+            err => CliError::Generator(err),
+        })?;
+
+    let (gen_info, grid_data) = generator.generate_grid().map_err(CliError::Generation)?;
+    eprintln!(
+        "Generated in {} tries, seed {}",
+        gen_info.try_count,
+        generator.seed()
+    );
+
+    if let Some(path) = &args.json_path {
+        write_file(
+            path,
+            &output::to_json(&grid_data, definition, generator.seed()),
+        )?;
+    }
+    if let Some(path) = &args.csv_path {
+        write_file(path, &output::to_csv(&grid_data, definition))?;
+    }
+    if !args.quiet {
+        let _ = write!(
+            io::stdout(),
+            "{}",
+            output::to_ascii(&grid_data, generator.rules())
+        );
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), CliError> {
+    fs::write(path, contents).map_err(|err| CliError::Io(path.display().to_string(), err))
+}
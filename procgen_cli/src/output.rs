@@ -0,0 +1,149 @@
+use std::fmt::Write;
+
+use procedural_tilemaps_core::{
+    generator::{model::ModelInstance, rules::Rules, rules_definition::RulesDefinition},
+    grid::{
+        cartesian::{coordinates::CartesianCoordinates, grid::CartesianGrid},
+        grid::GridData,
+    },
+};
+
+/// Key of the model metadata used as the character of the model in the ASCII preview
+pub const ASCII_METADATA_KEY: &str = "ascii";
+
+/// Characters used in the ASCII preview for models without an `ascii` metadata, by model index
+const DEFAULT_ASCII_CHARS: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Writes the generated models as a JSON object, with the grid size, the seed and one entry per node in index order
+pub fn to_json<C: CartesianCoordinates>(
+    grid_data: &GridData<C, ModelInstance, CartesianGrid<C>>,
+    definition: &RulesDefinition,
+    seed: u64,
+) -> String {
+    let grid = grid_data.grid();
+    let mut json = String::new();
+    json.push_str("{\n");
+    let _ = writeln!(
+        json,
+        "  \"size\": [{}, {}, {}],",
+        grid.size_x(),
+        grid.size_y(),
+        grid.size_z()
+    );
+    let _ = writeln!(json, "  \"seed\": {},", seed);
+    json.push_str("  \"nodes\": [");
+    for index in grid_data.indexes() {
+        let pos = grid.pos_from_index(index);
+        let instance = grid_data.get(index);
+        let name = match model_name(definition, instance) {
+            Some(name) => format!("\"{}\"", escape_json(name)),
+            None => "null".to_string(),
+        };
+        let _ = write!(
+            json,
+            "{}\n    {{\"x\": {}, \"y\": {}, \"z\": {}, \"model_index\": {}, \"rotation\": {}, \"flip\": \"{:?}\", \"name\": {}}}",
+            if index == 0 { "" } else { "," },
+            pos.x,
+            pos.y,
+            pos.z,
+            instance.model_index,
+            instance.rotation.value(),
+            instance.flip,
+            name
+        );
+    }
+    json.push_str("\n  ]\n}\n");
+    json
+}
+
+/// Writes the generated models as CSV, with a header and one row per node in index order
+pub fn to_csv<C: CartesianCoordinates>(
+    grid_data: &GridData<C, ModelInstance, CartesianGrid<C>>,
+    definition: &RulesDefinition,
+) -> String {
+    let grid = grid_data.grid();
+    let mut csv = String::from("x,y,z,model_index,rotation,flip,name\n");
+    for index in grid_data.indexes() {
+        let pos = grid.pos_from_index(index);
+        let instance = grid_data.get(index);
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{:?},{}",
+            pos.x,
+            pos.y,
+            pos.z,
+            instance.model_index,
+            instance.rotation.value(),
+            instance.flip,
+            model_name(definition, instance)
+                .map(escape_csv)
+                .unwrap_or_default()
+        );
+    }
+    csv
+}
+
+/// Draws the generated models with one character per node, each z layer from the top row to the bottom row.
+///
+/// A model is drawn with the first character of its `ascii` metadata if any, or else with a character derived from its index.
+pub fn to_ascii<C: CartesianCoordinates>(
+    grid_data: &GridData<C, ModelInstance, CartesianGrid<C>>,
+    rules: &Rules<C>,
+) -> String {
+    let grid = grid_data.grid();
+    let model_char = |instance: &ModelInstance| {
+        rules
+            .model_metadata(instance.model_index)
+            .and_then(|metadata| metadata.get(ASCII_METADATA_KEY))
+            .and_then(|ascii| ascii.chars().next())
+            .or_else(|| DEFAULT_ASCII_CHARS.chars().nth(instance.model_index))
+            .unwrap_or('?')
+    };
+
+    let mut ascii = String::new();
+    for z in 0..grid.size_z() {
+        if grid.size_z() > 1 {
+            let _ = writeln!(ascii, "z = {}", z);
+        }
+        for y in (0..grid.size_y()).rev() {
+            for x in 0..grid.size_x() {
+                ascii.push(model_char(grid_data.get(grid.index_from_coords(x, y, z))));
+            }
+            ascii.push('\n');
+        }
+    }
+    ascii
+}
+
+fn model_name<'a>(definition: &'a RulesDefinition, instance: &ModelInstance) -> Option<&'a str> {
+    definition
+        .models
+        .get(instance.model_index)
+        .and_then(|model| model.name.as_deref())
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+const BEACHES_RULES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/rules/beaches.rules.ron");
+
+/// Models which must alternate along x: always fails on a looping row of odd length
+const ALTERNATING_RULES: &str = r#"(
+    sockets: ["p_pos", "p_neg", "q_pos", "q_neg", "other"],
+    connections: [
+        ("p_pos", ["q_neg"]),
+        ("q_pos", ["p_neg"]),
+        ("other", ["other"]),
+    ],
+    models: [
+        (name: "p", sockets: Simple(x_pos: "p_pos", x_neg: "p_neg", y_pos: "other", y_neg: "other")),
+        (name: "q", sockets: Simple(x_pos: "q_pos", x_neg: "q_neg", y_pos: "other", y_neg: "other")),
+    ],
+)"#;
+
+/// Temporary directory removed on drop
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("procgen_cli-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn file(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.0.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn run<P: AsRef<Path>>(rules_path: P, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_procgen-cli"))
+        .arg(rules_path.as_ref())
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn exit_code(output: &Output) -> i32 {
+    output.status.code().unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn help_exits_with_0() {
+    let output = Command::new(env!("CARGO_BIN_EXE_procgen-cli"))
+        .arg("--help")
+        .output()
+        .unwrap();
+    assert_eq!(exit_code(&output), 0);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Usage: procgen-cli"));
+}
+
+#[test]
+fn invalid_arguments_exit_with_2() {
+    for args in [
+        &[][..],
+        &["--size", "0x3"],
+        &["--size", "4"],
+        &["--size", "4x3x2x1"],
+        &["--size", "4x3", "--loop-z"],
+        &["--size", "4x3", "--seed", "seven"],
+        &["--size", "4x3", "--heuristic", "best"],
+        &["--size", "4x3", "--unknown"],
+        &["--size", "4x3", "extra.ron"],
+        &["--size"],
+    ] {
+        let output = run(BEACHES_RULES, args);
+        assert_eq!(exit_code(&output), 2, "{:?}: {}", args, stderr(&output));
+        assert!(stderr(&output).contains("Usage:"), "{:?}", args);
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_procgen-cli"))
+        .args(["--size", "4x3"])
+        .output()
+        .unwrap();
+    assert_eq!(exit_code(&output), 2);
+    assert!(stderr(&output).contains("Missing rules file"));
+}
+
+#[test]
+fn file_errors_exit_with_1() {
+    let dir = TempDir::new("file-errors");
+
+    let output = run(dir.path("missing.rules.ron"), &["--size", "4x3"]);
+    assert_eq!(exit_code(&output), 1, "{}", stderr(&output));
+
+    let json_path = dir.path("missing-dir/map.json");
+    let output = run(
+        BEACHES_RULES,
+        &["--size", "4x3", "--json", json_path.to_str().unwrap()],
+    );
+    assert_eq!(exit_code(&output), 1, "{}", stderr(&output));
+}
+
+#[test]
+fn invalid_rules_exit_with_3() {
+    let dir = TempDir::new("invalid-rules");
+
+    let syntax_error = dir.file("syntax.rules.ron", "(sockets: [\"a\"");
+    let output = run(syntax_error, &["--size", "4x3"]);
+    assert_eq!(exit_code(&output), 3, "{}", stderr(&output));
+    assert!(stderr(&output).contains("Could not parse rules file"));
+
+    let unknown_socket = dir.file(
+        "unknown.rules.ron",
+        r#"(sockets: ["a"], connections: [("a", ["b"])], models: [(sockets: Mono("a"))])"#,
+    );
+    let output = run(unknown_socket, &["--size", "4x3"]);
+    assert_eq!(exit_code(&output), 3, "{}", stderr(&output));
+
+    let duplicate_socket = dir.file(
+        "duplicate.rules.ron",
+        r#"(sockets: ["a", "a"], connections: [("a", ["a"])], models: [(sockets: Mono("a"))])"#,
+    );
+    let output = run(duplicate_socket, &["--size", "4x3"]);
+    assert_eq!(exit_code(&output), 3, "{}", stderr(&output));
+    assert!(stderr(&output).contains("Duplicate socket name: a"));
+
+    // The alternating models have no sockets on the z axis
+    let missing_z_sockets = dir.file("alternating.rules.ron", ALTERNATING_RULES);
+    let output = run(missing_z_sockets, &["--size", "4x3x2"]);
+    assert_eq!(exit_code(&output), 3, "{}", stderr(&output));
+}
+
+#[test]
+fn failed_generation_exits_with_4() {
+    let dir = TempDir::new("failed-generation");
+    let rules_path = dir.file("alternating.rules.ron", ALTERNATING_RULES);
+
+    let output = run(
+        &rules_path,
+        &["--size", "3x1", "--loop-x", "--retries", "2"],
+    );
+    assert_eq!(exit_code(&output), 4, "{}", stderr(&output));
+
+    // Even rows can alternate
+    let output = run(&rules_path, &["--size", "4x1", "--loop-x", "--quiet"]);
+    assert_eq!(exit_code(&output), 0, "{}", stderr(&output));
+}
+
+#[test]
+fn writes_json_csv_and_ascii() {
+    let dir = TempDir::new("outputs");
+    let (json_path, csv_path) = (dir.path("map.json"), dir.path("map.csv"));
+    let output = run(
+        BEACHES_RULES,
+        &[
+            "--size",
+            "4x3",
+            "--seed",
+            "7",
+            "--json",
+            json_path.to_str().unwrap(),
+            "--csv",
+            csv_path.to_str().unwrap(),
+        ],
+    );
+    assert_eq!(exit_code(&output), 0, "{}", stderr(&output));
+    assert!(stderr(&output).contains("seed 7"));
+
+    let ascii = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<&str> = ascii.lines().collect();
+    assert_eq!(rows.len(), 3);
+    for row in rows {
+        assert_eq!(row.len(), 4);
+        assert!(row.chars().all(|c| ",.~".contains(c)), "{}", row);
+    }
+
+    let json = fs::read_to_string(&json_path).unwrap();
+    assert!(json.starts_with("{\n  \"size\": [4, 3, 1],\n  \"seed\": 7,\n  \"nodes\": ["));
+    assert!(json.ends_with("\n  ]\n}\n"));
+    assert_eq!(json.matches("\"model_index\"").count(), 12);
+    assert!(json.contains("\n    {\"x\": 0, \"y\": 0, \"z\": 0, \"model_index\": "));
+    assert!(json.contains(",\n    {\"x\": 3, \"y\": 2, \"z\": 0, "));
+
+    let csv = fs::read_to_string(&csv_path).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("x,y,z,model_index,rotation,flip,name"));
+    let rows: Vec<&str> = lines.collect();
+    assert_eq!(rows.len(), 12);
+    assert!(rows[0].starts_with("0,0,0,"));
+    assert!(rows[11].starts_with("3,2,0,"));
+    for row in rows {
+        let fields: Vec<&str> = row.split(',').collect();
+        assert_eq!(fields.len(), 7, "{}", row);
+        assert_eq!((fields[4], fields[5]), ("0", "None"));
+        assert!(["grass", "sand", "water"].contains(&fields[6]), "{}", row);
+    }
+}
+
+#[test]
+fn seeded_generations_are_reproducible() {
+    let dir = TempDir::new("reproducible");
+    let generate = |name: &str| {
+        let path = dir.path(name);
+        let output = run(
+            BEACHES_RULES,
+            &[
+                "--size",
+                "6x5x2",
+                "--seed",
+                "42",
+                "--quiet",
+                "--json",
+                path.to_str().unwrap(),
+            ],
+        );
+        assert_eq!(exit_code(&output), 0, "{}", stderr(&output));
+        assert!(output.stdout.is_empty());
+        fs::read_to_string(path).unwrap()
+    };
+    let json = generate("first.json");
+    assert!(json.contains("\"size\": [6, 5, 2]"));
+    assert_eq!(json, generate("second.json"));
+}
+
+#[test]
+fn escapes_model_names() {
+    let dir = TempDir::new("escapes");
+    let rules_path = dir.file(
+        "names.rules.ron",
+        r#"(
+            sockets: ["a"],
+            connections: [("a", ["a"])],
+            models: [(name: "quote \" comma , tab \t", sockets: Mono("a"))],
+        )"#,
+    );
+    let (json_path, csv_path) = (dir.path("map.json"), dir.path("map.csv"));
+    let output = run(
+        &rules_path,
+        &[
+            "--size",
+            "1x1",
+            "--quiet",
+            "--json",
+            json_path.to_str().unwrap(),
+            "--csv",
+            csv_path.to_str().unwrap(),
+        ],
+    );
+    assert_eq!(exit_code(&output), 0, "{}", stderr(&output));
+
+    let json = fs::read_to_string(json_path).unwrap();
+    assert!(
+        json.contains(r#""name": "quote \" comma , tab \t"}"#),
+        "{}",
+        json
+    );
+    let csv = fs::read_to_string(csv_path).unwrap();
+    assert!(csv.ends_with(",\"quote \"\" comma , tab \t\"\n"), "{}", csv);
+}